@@ -16,22 +16,21 @@ impl Default for TimeSeries {
     }
 }
 
-struct OHLCA {
+struct Ohlca {
     open: f64,
     high: f64,
     low: f64,
     close: f64,
     volume: f64,
-    from: u64,
-    to: u64,
+    window: TimeWindow,
 }
 
-impl std::fmt::Debug for OHLCA {
+impl std::fmt::Debug for Ohlca {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "OHLCA {{ open: {}, high: {}, low: {}, close: {}, volume: {}, from: {}, to: {} }}",
-            self.open, self.high, self.low, self.close, self.volume, self.from, self.to
+            "Ohlca {{ open: {}, high: {}, low: {}, close: {}, volume: {}, window: {} }}",
+            self.open, self.high, self.low, self.close, self.volume, self.window
         )
     }
 }
@@ -41,40 +40,38 @@ struct TestTaskA;
 #[derive(Clone)]
 struct TestTaskB;
 
-impl Runnable<TimeSeries, OHLCA> for TestTaskA {
+impl Runnable<TimeSeries, Ohlca> for TestTaskA {
     fn name(&self) -> String {
         "TestTask_A".to_string()
     }
 
-    fn run(&self, data: &TimeSeries, start: u64, end: u64) -> Result<OHLCA, TaskError<OHLCA>> {
+    fn run(&self, data: &TimeSeries, window: TimeWindow) -> Result<Ohlca, TaskError<Ohlca>> {
         let values: Vec<_> = data.time_series.values().collect();
-        Ok(OHLCA {
+        Ok(Ohlca {
             open: *values[0] as f64,
             high: **values.iter().max().unwrap() as f64,
             low: **values.iter().max().unwrap() as f64,
             close: *values[values.len() - 1] as f64,
             volume: values.len() as f64,
-            from: start,
-            to: end,
+            window,
         })
     }
 }
 
-impl Runnable<TimeSeries, OHLCA> for TestTaskB {
+impl Runnable<TimeSeries, Ohlca> for TestTaskB {
     fn name(&self) -> String {
         "TestTask_B".to_string()
     }
 
-    fn run(&self, data: &TimeSeries, start: u64, end: u64) -> Result<OHLCA, TaskError<OHLCA>> {
+    fn run(&self, data: &TimeSeries, window: TimeWindow) -> Result<Ohlca, TaskError<Ohlca>> {
         let values: Vec<_> = data.time_series.values().collect();
-        Ok(OHLCA {
+        Ok(Ohlca {
             open: *values[0] as f64 * 100.0,
             high: **values.iter().max().unwrap() as f64 * 100.0,
             low: **values.iter().max().unwrap() as f64 * 100.0,
             close: *values[values.len() - 1] as f64 * 100.0,
             volume: values.len() as f64 * 100.0,
-            from: start,
-            to: end,
+            window,
         })
     }
 }
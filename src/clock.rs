@@ -0,0 +1,95 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Utc};
+use tokio::sync::watch;
+use tokio::time::{self, Instant};
+
+use crate::task::BoxFuture;
+
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+    fn sleep_until(&self, deadline: DateTime<Utc>) -> BoxFuture<'_, ()>;
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+
+    fn sleep_until(&self, deadline: DateTime<Utc>) -> BoxFuture<'_, ()> {
+        let wait = (deadline - Utc::now()).to_std().unwrap_or_default();
+        Box::pin(time::sleep(wait))
+    }
+}
+
+// Follows tokio's clock, so it stands still and auto-advances along with
+// `tokio::time::pause()` in tests.
+#[derive(Clone, Copy, Debug)]
+pub struct TokioClock {
+    origin: DateTime<Utc>,
+    started: Instant,
+}
+
+impl TokioClock {
+    pub fn new(origin: DateTime<Utc>) -> Self {
+        TokioClock {
+            origin,
+            started: Instant::now(),
+        }
+    }
+}
+
+impl Default for TokioClock {
+    fn default() -> Self {
+        TokioClock::new(Utc::now())
+    }
+}
+
+impl Clock for TokioClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.origin + Duration::from_std(self.started.elapsed()).unwrap_or(Duration::MAX)
+    }
+
+    fn sleep_until(&self, deadline: DateTime<Utc>) -> BoxFuture<'_, ()> {
+        let offset = (deadline - self.origin).to_std().unwrap_or_default();
+        Box::pin(time::sleep_until(self.started + offset))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ManualClock {
+    time: Arc<watch::Sender<DateTime<Utc>>>,
+}
+
+impl ManualClock {
+    pub fn new(start: DateTime<Utc>) -> Self {
+        ManualClock {
+            time: Arc::new(watch::Sender::new(start)),
+        }
+    }
+
+    pub fn set(&self, time: DateTime<Utc>) {
+        self.time.send_replace(time);
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        self.time.send_modify(|time| *time += duration);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.time.borrow()
+    }
+
+    fn sleep_until(&self, deadline: DateTime<Utc>) -> BoxFuture<'_, ()> {
+        let mut time = self.time.subscribe();
+        Box::pin(async move {
+            // The sender lives as long as the clock, so this cannot fail.
+            let _ = time.wait_for(|now| *now >= deadline).await;
+        })
+    }
+}
@@ -1,23 +1,130 @@
+use std::fmt;
+use std::sync::Arc;
 use tokio::time::Duration;
 
-#[derive(Clone, Debug)]
-pub struct RunnerConfig {
+use crate::pool::TaskPool;
+use crate::schedule::Schedule;
+
+#[derive(Default)]
+pub enum MissingResultPolicy<Output> {
+    #[default]
+    Ignore,
+    Fallback(Arc<dyn Fn(&str) -> Output + Send + Sync>),
+    CarryForward(Arc<dyn Fn(&Output) -> Output + Send + Sync>),
+    DropDataSet,
+}
+
+impl<Output> MissingResultPolicy<Output> {
+    pub fn fallback(f: impl Fn(&str) -> Output + Send + Sync + 'static) -> Self {
+        MissingResultPolicy::Fallback(Arc::new(f))
+    }
+
+    pub fn carry_forward() -> Self
+    where
+        Output: Clone + 'static,
+    {
+        MissingResultPolicy::CarryForward(Arc::new(Output::clone))
+    }
+}
+
+impl<Output> Clone for MissingResultPolicy<Output> {
+    fn clone(&self) -> Self {
+        match self {
+            MissingResultPolicy::Ignore => MissingResultPolicy::Ignore,
+            MissingResultPolicy::Fallback(f) => MissingResultPolicy::Fallback(Arc::clone(f)),
+            MissingResultPolicy::CarryForward(f) => {
+                MissingResultPolicy::CarryForward(Arc::clone(f))
+            }
+            MissingResultPolicy::DropDataSet => MissingResultPolicy::DropDataSet,
+        }
+    }
+}
+
+impl<Output> fmt::Debug for MissingResultPolicy<Output> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MissingResultPolicy::Ignore => write!(f, "Ignore"),
+            MissingResultPolicy::Fallback(_) => write!(f, "Fallback"),
+            MissingResultPolicy::CarryForward(_) => write!(f, "CarryForward"),
+            MissingResultPolicy::DropDataSet => write!(f, "DropDataSet"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MissedTickPolicy {
+    #[default]
+    Burst,
+    Delay,
+    Skip,
+}
+
+// What the runner does with a DataSet once the result channel is full.
+// `Block` waits for the consumer and holds up later ticks. The others never
+// wait: `DropOldest` drops the oldest queued DataSet, `DropNewest` discards
+// the new one, and `Coalesce` merges the new one into the newest queued
+// DataSet of the same cadence, falling back to dropping the oldest.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BackpressurePolicy {
+    #[default]
+    Block,
+    DropOldest,
+    DropNewest,
+    Coalesce,
+}
+
+pub struct RunnerConfig<Output> {
     pub task_channel_capacity: usize,
     pub broadcast_channel_capacity: usize,
     pub shutdown_timeout: Duration,
+    pub missing_results: MissingResultPolicy<Output>,
+    pub missed_ticks: MissedTickPolicy,
+    pub backpressure: BackpressurePolicy,
 }
 
-impl Default for RunnerConfig {
+impl<Output> Default for RunnerConfig<Output> {
     fn default() -> Self {
         Self {
             task_channel_capacity: 1024,
             broadcast_channel_capacity: 16,
             shutdown_timeout: Duration::from_secs(5),
+            missing_results: MissingResultPolicy::default(),
+            missed_ticks: MissedTickPolicy::default(),
+            backpressure: BackpressurePolicy::default(),
         }
     }
 }
 
-impl RunnerConfig {
+impl<Output> Clone for RunnerConfig<Output> {
+    fn clone(&self) -> Self {
+        Self {
+            task_channel_capacity: self.task_channel_capacity,
+            broadcast_channel_capacity: self.broadcast_channel_capacity,
+            shutdown_timeout: self.shutdown_timeout,
+            missing_results: self.missing_results.clone(),
+            missed_ticks: self.missed_ticks,
+            backpressure: self.backpressure,
+        }
+    }
+}
+
+impl<Output> fmt::Debug for RunnerConfig<Output> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RunnerConfig")
+            .field("task_channel_capacity", &self.task_channel_capacity)
+            .field(
+                "broadcast_channel_capacity",
+                &self.broadcast_channel_capacity,
+            )
+            .field("shutdown_timeout", &self.shutdown_timeout)
+            .field("missing_results", &self.missing_results)
+            .field("missed_ticks", &self.missed_ticks)
+            .field("backpressure", &self.backpressure)
+            .finish()
+    }
+}
+
+impl<Output> RunnerConfig<Output> {
     pub fn new(
         task_channel_capacity: usize,
         broadcast_channel_capacity: usize,
@@ -27,6 +134,97 @@ impl RunnerConfig {
             task_channel_capacity,
             broadcast_channel_capacity,
             shutdown_timeout,
+            missing_results: MissingResultPolicy::default(),
+            missed_ticks: MissedTickPolicy::default(),
+            backpressure: BackpressurePolicy::default(),
+        }
+    }
+
+    pub fn with_missing_results(mut self, policy: MissingResultPolicy<Output>) -> Self {
+        self.missing_results = policy;
+        self
+    }
+
+    pub fn with_missed_ticks(mut self, policy: MissedTickPolicy) -> Self {
+        self.missed_ticks = policy;
+        self
+    }
+
+    pub fn with_backpressure(mut self, policy: BackpressurePolicy) -> Self {
+        self.backpressure = policy;
+        self
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub enum ExecutionMode {
+    #[default]
+    Inline,
+    Blocking,
+    Pool(Arc<TaskPool>),
+}
+
+#[derive(Clone, Debug, Default)]
+pub enum FailurePolicy {
+    #[default]
+    Skip,
+    Retry {
+        max_retries: usize,
+        backoff: Duration,
+    },
+    Disable,
+    Abort,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct TaskConfig {
+    pub mode: ExecutionMode,
+    pub timeout: Option<Duration>,
+    pub on_failure: FailurePolicy,
+    pub schedule: Option<Schedule>,
+}
+
+impl TaskConfig {
+    pub fn new(mode: ExecutionMode) -> Self {
+        Self {
+            mode,
+            timeout: None,
+            on_failure: FailurePolicy::default(),
+            schedule: None,
+        }
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn with_failure_policy(mut self, policy: FailurePolicy) -> Self {
+        self.on_failure = policy;
+        self
+    }
+
+    pub fn with_schedule(mut self, schedule: impl Into<Schedule>) -> Self {
+        self.schedule = Some(schedule.into());
+        self
+    }
+
+    // The longest a tick can wait on this task, covering every retry attempt
+    // and the backoff between them. Waiting on the read lock counts towards
+    // an attempt's timeout.
+    pub(crate) fn deadline(&self) -> Option<Duration> {
+        let attempt = self.timeout?;
+        match self.on_failure {
+            FailurePolicy::Retry {
+                max_retries,
+                backoff,
+            } => {
+                let backoffs = (0..max_retries)
+                    .map(|retry| backoff.saturating_mul(2u32.saturating_pow(retry as u32)))
+                    .fold(Duration::ZERO, Duration::saturating_add);
+                Some(attempt.saturating_mul(max_retries as u32 + 1) + backoffs)
+            }
+            _ => Some(attempt),
         }
     }
 }
@@ -1,13 +1,22 @@
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use tokio::sync::mpsc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 
-use crate::config::RunnerConfig;
-use crate::data_types::DataSet;
+use crate::clock::{Clock, SystemClock};
+use crate::config::{RunnerConfig, TaskConfig};
+use crate::data_types::{AnyOutput, TaskKey};
+use crate::error::BuildError;
+use crate::graph::{self, Dependencies};
 use crate::interval::TaskInterval;
-use crate::task::Runnable;
+use crate::queue::{data_channel, DataReceiver, DataSender};
+use crate::schedule::{Schedule, TickTiming};
+use crate::source::DataSource;
+use crate::stateful::{Stateful, StatefulRunnable};
+use crate::task::{AsyncRunnable, Runnable, Task, TaskSpec, Typed};
 
-pub type DataReceiver<Output> = mpsc::Receiver<DataSet<Output>>;
+pub type Snapshot<Input> = Arc<dyn Fn(&Input) -> Input + Send + Sync>;
+pub type OutputClone<Output> = Arc<dyn Fn(&Output) -> Output + Send + Sync>;
 pub type BuildResult<Input, Output> = (
     Context<Input, Output>,
     DataReceiver<Output>,
@@ -15,26 +24,38 @@ pub type BuildResult<Input, Output> = (
 );
 
 pub struct Context<Input, Output> {
-    pub config: RunnerConfig,
-    pub tasks: Vec<Arc<dyn Runnable<Input, Output>>>,
+    pub config: RunnerConfig<Output>,
+    pub tasks: Vec<TaskSpec<Input, Output>>,
     pub data: Arc<RwLock<Input>>,
-    pub interval: TaskInterval,
-    pub sender: mpsc::Sender<DataSet<Output>>,
+    pub schedule: Schedule,
+    pub clock: Arc<dyn Clock>,
+    pub timing: TickTiming,
+    pub snapshot: Option<Snapshot<Input>>,
+    pub sources: Vec<Arc<dyn DataSource<Input>>>,
+    pub dependencies: Dependencies,
+    pub output_clone: Option<OutputClone<Output>>,
+    pub sender: DataSender<Output>,
 }
 
 impl<Input, Output> Context<Input, Output> {
     pub fn new(
-        config: RunnerConfig,
-        tasks: Vec<Arc<dyn Runnable<Input, Output>>>,
+        config: RunnerConfig<Output>,
+        tasks: Vec<TaskSpec<Input, Output>>,
         data: Arc<RwLock<Input>>,
-        interval: TaskInterval,
+        schedule: impl Into<Schedule>,
     ) -> (Self, DataReceiver<Output>) {
-        let (sender, receiver) = mpsc::channel(config.task_channel_capacity);
+        let (sender, receiver) = data_channel(config.task_channel_capacity, config.backpressure);
         let ctx = Context {
             config,
             tasks,
             data,
-            interval,
+            schedule: schedule.into(),
+            clock: Arc::new(SystemClock),
+            timing: TickTiming::default(),
+            snapshot: None,
+            sources: Vec::new(),
+            dependencies: Dependencies::new(),
+            output_clone: None,
             sender,
         };
         (ctx, receiver)
@@ -42,10 +63,18 @@ impl<Input, Output> Context<Input, Output> {
 }
 
 pub struct ContextBuilder<Input: Default, Output> {
-    tasks: Vec<Arc<dyn Runnable<Input, Output>>>,
+    tasks: Vec<TaskSpec<Input, Output>>,
     data: Option<Arc<RwLock<Input>>>,
-    interval: TaskInterval,
-    config: RunnerConfig,
+    schedule: Schedule,
+    clock: Arc<dyn Clock>,
+    timing: TickTiming,
+    snapshot: Option<Snapshot<Input>>,
+    sources: Vec<Arc<dyn DataSource<Input>>>,
+    dependencies: Dependencies,
+    output_clone: Option<OutputClone<Output>>,
+    config: RunnerConfig<Output>,
+    // (key, task) names of typed tasks registered under the wrong key.
+    mismatched_keys: Vec<(String, String)>,
 }
 
 impl<Input: Default, Output> ContextBuilder<Input, Output> {
@@ -53,8 +82,15 @@ impl<Input: Default, Output> ContextBuilder<Input, Output> {
         ContextBuilder {
             tasks: Vec::new(),
             data: None,
-            interval: TaskInterval::Seconds(5),
+            schedule: Schedule::Interval(TaskInterval::Seconds(5)),
+            clock: Arc::new(SystemClock),
+            timing: TickTiming::default(),
+            snapshot: None,
+            sources: Vec::new(),
+            dependencies: Dependencies::new(),
+            output_clone: None,
             config: RunnerConfig::default(),
+            mismatched_keys: Vec::new(),
         }
     }
 
@@ -63,34 +99,215 @@ impl<Input: Default, Output> ContextBuilder<Input, Output> {
         self
     }
 
-    pub fn with_config(mut self, config: RunnerConfig) -> Self {
+    pub fn with_config(mut self, config: RunnerConfig<Output>) -> Self {
         self.config = config;
         self
     }
 
-    pub fn with_task(mut self, task: impl Runnable<Input, Output> + 'static) -> Self {
-        self.tasks.push(Arc::new(task));
+    pub fn with_task(self, task: impl Runnable<Input, Output> + 'static) -> Self {
+        self.with_task_config(task, TaskConfig::default())
+    }
+
+    pub fn with_task_config(
+        mut self,
+        task: impl Runnable<Input, Output> + 'static,
+        config: TaskConfig,
+    ) -> Self {
+        self.tasks
+            .push(TaskSpec::new(Task::Sync(Arc::new(task)), config));
+        self
+    }
+
+    pub fn with_async_task(self, task: impl AsyncRunnable<Input, Output> + 'static) -> Self {
+        self.with_async_task_config(task, TaskConfig::default())
+    }
+
+    pub fn with_async_task_config(
+        mut self,
+        task: impl AsyncRunnable<Input, Output> + 'static,
+        config: TaskConfig,
+    ) -> Self {
+        self.tasks
+            .push(TaskSpec::new(Task::Async(Arc::new(task)), config));
         self
     }
 
     pub fn with_tasks(mut self, tasks: Vec<impl Runnable<Input, Output> + 'static>) -> Self {
         for task in tasks {
-            self.tasks.push(Arc::new(task));
+            self.tasks.push(TaskSpec::new(
+                Task::Sync(Arc::new(task)),
+                TaskConfig::default(),
+            ));
         }
         self
     }
 
-    pub fn with_interval(mut self, interval: TaskInterval) -> Self {
-        self.interval = interval;
+    pub fn with_stateful_task<R>(self, task: R) -> Self
+    where
+        R: StatefulRunnable<Input, Output> + 'static,
+        Input: 'static,
+        Output: Send + 'static,
+    {
+        self.with_task(Stateful::new(task))
+    }
+
+    pub fn with_interval(mut self, interval: impl Into<TaskInterval>) -> Self {
+        self.schedule = Schedule::Interval(interval.into());
+        self
+    }
+
+    pub fn with_schedule(mut self, schedule: impl Into<Schedule>) -> Self {
+        self.schedule = schedule.into();
+        self
+    }
+
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    pub fn with_start_delay(mut self, delay: Duration) -> Self {
+        self.timing.start_delay = delay;
+        self
+    }
+
+    pub fn with_phase_offset(mut self, phase: Duration) -> Self {
+        self.timing.phase = phase;
+        self
+    }
+
+    pub fn with_jitter(mut self, jitter: Duration) -> Self {
+        self.timing.jitter = jitter;
+        self
+    }
+
+    pub fn with_snapshot(
+        mut self,
+        snapshot: impl Fn(&Input) -> Input + Send + Sync + 'static,
+    ) -> Self {
+        self.snapshot = Some(Arc::new(snapshot));
+        self
+    }
+
+    pub fn with_source(mut self, source: impl DataSource<Input> + 'static) -> Self {
+        self.sources.push(Arc::new(source));
+        self
+    }
+
+    pub fn with_cloned_snapshot(self) -> Self
+    where
+        Input: Clone + 'static,
+    {
+        self.with_snapshot(Input::clone)
+    }
+
+    // `task` runs after `upstream` within every tick, and is skipped when
+    // `upstream` fails. Both tasks need to run on the same schedule.
+    pub fn with_dependency(mut self, task: &str, upstream: &str) -> Self {
+        let upstreams = self.dependencies.entry(task.to_string()).or_default();
+        if !upstreams.iter().any(|name| name == upstream) {
+            upstreams.push(upstream.to_string());
+        }
         self
     }
 
+    pub fn with_output_clone(
+        mut self,
+        clone: impl Fn(&Output) -> Output + Send + Sync + 'static,
+    ) -> Self {
+        self.output_clone = Some(Arc::new(clone));
+        self
+    }
+
+    pub fn with_cloned_outputs(self) -> Self
+    where
+        Output: Clone + 'static,
+    {
+        self.with_output_clone(Output::clone)
+    }
+
     pub fn build(self) -> BuildResult<Input, Output> {
+        self.try_build()
+            .unwrap_or_else(|e| panic!("Invalid runner context: {}", e))
+    }
+
+    pub fn try_build(self) -> Result<BuildResult<Input, Output>, BuildError> {
+        self.validate()?;
         let data = self
             .data
             .unwrap_or_else(|| Arc::new(RwLock::new(Input::default())));
-        let (ctx, rx) = Context::new(self.config, self.tasks, Arc::clone(&data), self.interval);
-        (ctx, rx, data)
+        let (mut ctx, rx) = Context::new(self.config, self.tasks, Arc::clone(&data), self.schedule);
+        ctx.clock = self.clock;
+        ctx.timing = self.timing;
+        ctx.snapshot = self.snapshot;
+        ctx.sources = self.sources;
+        ctx.dependencies = self.dependencies;
+        ctx.output_clone = self.output_clone;
+        Ok((ctx, rx, data))
+    }
+
+    fn validate(&self) -> Result<(), BuildError> {
+        if let Some((key, task)) = self.mismatched_keys.first() {
+            return Err(BuildError::KeyMismatch {
+                key: key.clone(),
+                task: task.clone(),
+            });
+        }
+        let mut names = HashSet::new();
+        if let Some(spec) = self
+            .tasks
+            .iter()
+            .find(|spec| !names.insert(spec.task.name()))
+        {
+            return Err(BuildError::DuplicateTask(spec.task.name()));
+        }
+        if self.schedule.is_zero() {
+            return Err(BuildError::ZeroInterval("the runner".to_string()));
+        }
+        if let Some(spec) = self
+            .tasks
+            .iter()
+            .find(|spec| spec.config.schedule.as_ref().is_some_and(Schedule::is_zero))
+        {
+            return Err(BuildError::ZeroInterval(spec.task.name()));
+        }
+
+        if self.dependencies.is_empty() {
+            return Ok(());
+        }
+        if self.output_clone.is_none() {
+            return Err(BuildError::MissingOutputClone);
+        }
+
+        let schedules: HashMap<String, &Schedule> = self
+            .tasks
+            .iter()
+            .map(|spec| {
+                let schedule = spec.config.schedule.as_ref().unwrap_or(&self.schedule);
+                (spec.task.name(), schedule)
+            })
+            .collect();
+        for (task, upstreams) in &self.dependencies {
+            let schedule = schedules
+                .get(task)
+                .ok_or_else(|| BuildError::UnknownTask(task.clone()))?;
+            for upstream in upstreams {
+                let upstream_schedule = schedules
+                    .get(upstream)
+                    .ok_or_else(|| BuildError::UnknownTask(upstream.clone()))?;
+                if schedule != upstream_schedule {
+                    return Err(BuildError::ScheduleMismatch {
+                        task: task.clone(),
+                        upstream: upstream.clone(),
+                    });
+                }
+            }
+        }
+
+        let names: Vec<&str> = schedules.keys().map(String::as_str).collect();
+        graph::layers(&names, &self.dependencies)
+            .map(|_| ())
+            .map_err(BuildError::Cycle)
     }
 
     pub fn get_data_or_default(&self) -> Arc<RwLock<Input>> {
@@ -100,6 +317,61 @@ impl<Input: Default, Output> ContextBuilder<Input, Output> {
     }
 }
 
+// Typed tasks are registered under a `TaskKey` naming the task, so the result
+// can be read back with its concrete type.
+impl<Input: Default + Sync + 'static> ContextBuilder<Input, AnyOutput> {
+    pub fn with_typed_task<T: Send + Sync + 'static>(
+        self,
+        task: impl Runnable<Input, T> + 'static,
+        key: &TaskKey<T>,
+    ) -> Self {
+        self.with_typed_task_config(task, TaskConfig::default(), key)
+    }
+
+    pub fn with_typed_task_config<T: Send + Sync + 'static>(
+        mut self,
+        task: impl Runnable<Input, T> + 'static,
+        config: TaskConfig,
+        key: &TaskKey<T>,
+    ) -> Self {
+        self.check_key(key, &task.name());
+        self.tasks.push(TaskSpec::new(
+            Task::Sync(Arc::new(Typed::new(task))),
+            config,
+        ));
+        self
+    }
+
+    pub fn with_typed_async_task<T: Send + Sync + 'static>(
+        self,
+        task: impl AsyncRunnable<Input, T> + 'static,
+        key: &TaskKey<T>,
+    ) -> Self {
+        self.with_typed_async_task_config(task, TaskConfig::default(), key)
+    }
+
+    pub fn with_typed_async_task_config<T: Send + Sync + 'static>(
+        mut self,
+        task: impl AsyncRunnable<Input, T> + 'static,
+        config: TaskConfig,
+        key: &TaskKey<T>,
+    ) -> Self {
+        self.check_key(key, &task.name());
+        self.tasks.push(TaskSpec::new(
+            Task::Async(Arc::new(Typed::new(task))),
+            config,
+        ));
+        self
+    }
+
+    fn check_key<T>(&mut self, key: &TaskKey<T>, task: &str) {
+        if key.name() != task {
+            self.mismatched_keys
+                .push((key.name().to_string(), task.to_string()));
+        }
+    }
+}
+
 impl<Input: Default, Output> Default for ContextBuilder<Input, Output> {
     fn default() -> Self {
         Self::new()
@@ -0,0 +1,195 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Datelike, Duration, TimeZone, Timelike, Utc};
+
+use crate::error::ParseError;
+
+// Searching further than this without a match means the expression can
+// never fire (e.g. "0 0 31 2 *").
+const MAX_SEARCH_DAYS: i64 = 366 * 5;
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    expression: String,
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    any_day_of_month: bool,
+    any_day_of_week: bool,
+}
+
+impl CronSchedule {
+    pub fn parse(expression: &str) -> Result<Self, ParseError> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(ParseError::InvalidCron(format!(
+                "expected 5 fields, got {} in '{}'",
+                fields.len(),
+                expression
+            )));
+        }
+
+        let mut days_of_week = parse_field(fields[4], 0, 7)?;
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week = (days_of_week | 1) & !(1 << 7);
+        }
+
+        Ok(CronSchedule {
+            expression: fields.join(" "),
+            minutes: parse_field(fields[0], 0, 59)?,
+            hours: parse_field(fields[1], 0, 23)?,
+            days_of_month: parse_field(fields[2], 1, 31)?,
+            months: parse_field(fields[3], 1, 12)?,
+            days_of_week,
+            any_day_of_month: fields[2] == "*",
+            any_day_of_week: fields[4] == "*",
+        })
+    }
+
+    pub fn next_after(&self, time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let limit = time + Duration::days(MAX_SEARCH_DAYS);
+        let mut t = truncate_to_minute(time) + Duration::minutes(1);
+
+        while t <= limit {
+            if !contains(self.months, t.month()) {
+                t = start_of_month(t.year(), t.month() + 1);
+            } else if !self.matches_day(t) {
+                t = start_of_day(t) + Duration::days(1);
+            } else if !contains(self.hours, t.hour()) {
+                t = start_of_hour(t) + Duration::hours(1);
+            } else if !contains(self.minutes, t.minute()) {
+                t += Duration::minutes(1);
+            } else {
+                return Some(t);
+            }
+        }
+        None
+    }
+
+    pub fn prev_before(&self, time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let limit = time - Duration::days(MAX_SEARCH_DAYS);
+        let mut t = truncate_to_minute(time);
+        if t == time {
+            t -= Duration::minutes(1);
+        }
+
+        while t >= limit {
+            if !contains(self.months, t.month()) {
+                t = start_of_month(t.year(), t.month()) - Duration::minutes(1);
+            } else if !self.matches_day(t) {
+                t = start_of_day(t) - Duration::minutes(1);
+            } else if !contains(self.hours, t.hour()) {
+                t = start_of_hour(t) - Duration::minutes(1);
+            } else if !contains(self.minutes, t.minute()) {
+                t -= Duration::minutes(1);
+            } else {
+                return Some(t);
+            }
+        }
+        None
+    }
+
+    fn matches_day(&self, t: DateTime<Utc>) -> bool {
+        let dom = contains(self.days_of_month, t.day());
+        let dow = contains(self.days_of_week, t.weekday().num_days_from_sunday());
+        match (self.any_day_of_month, self.any_day_of_week) {
+            (true, true) => true,
+            (false, true) => dom,
+            (true, false) => dow,
+            (false, false) => dom || dow,
+        }
+    }
+}
+
+impl FromStr for CronSchedule {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        CronSchedule::parse(s)
+    }
+}
+
+impl fmt::Display for CronSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.expression)
+    }
+}
+
+impl fmt::Debug for CronSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("CronSchedule")
+            .field(&self.expression)
+            .finish()
+    }
+}
+
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64, ParseError> {
+    let invalid =
+        |reason: &str| ParseError::InvalidCron(format!("{} in field '{}'", reason, field));
+    let mut bits = 0u64;
+
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (
+                range,
+                step.parse::<u32>()
+                    .ok()
+                    .filter(|step| *step > 0)
+                    .ok_or_else(|| invalid("invalid step"))?,
+            ),
+            None => (part, 1),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            let start = start.parse().map_err(|_| invalid("invalid value"))?;
+            let end = end.parse().map_err(|_| invalid("invalid value"))?;
+            (start, end)
+        } else {
+            let value = range.parse().map_err(|_| invalid("invalid value"))?;
+            if part.contains('/') {
+                (value, max)
+            } else {
+                (value, value)
+            }
+        };
+
+        if start < min || end > max || start > end {
+            return Err(invalid("value out of range"));
+        }
+        for value in (start..=end).step_by(step as usize) {
+            bits |= 1 << value;
+        }
+    }
+    Ok(bits)
+}
+
+fn contains(bits: u64, value: u32) -> bool {
+    bits & (1 << value) != 0
+}
+
+fn truncate_to_minute(t: DateTime<Utc>) -> DateTime<Utc> {
+    start_of_hour(t) + Duration::minutes(t.minute() as i64)
+}
+
+fn start_of_hour(t: DateTime<Utc>) -> DateTime<Utc> {
+    start_of_day(t) + Duration::hours(t.hour() as i64)
+}
+
+fn start_of_day(t: DateTime<Utc>) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(t.year(), t.month(), t.day(), 0, 0, 0)
+        .unwrap()
+}
+
+fn start_of_month(year: i32, month: u32) -> DateTime<Utc> {
+    let (year, month) = if month > 12 {
+        (year + 1, 1)
+    } else {
+        (year, month)
+    };
+    Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0).unwrap()
+}
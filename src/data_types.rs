@@ -1,22 +1,180 @@
+use std::any::Any;
 use std::collections::HashMap;
 use std::fmt;
+use std::marker::PhantomData;
+
+use crate::schedule::Schedule;
+use crate::window::TimeWindow;
+
+pub type AnyOutput = Box<dyn Any + Send + Sync>;
+
+pub struct TaskKey<T> {
+    name: String,
+    _output: PhantomData<fn() -> T>,
+}
+
+impl<T> TaskKey<T> {
+    pub fn new(name: &str) -> Self {
+        TaskKey {
+            name: name.to_string(),
+            _output: PhantomData,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<T> Clone for TaskKey<T> {
+    fn clone(&self) -> Self {
+        TaskKey::new(&self.name)
+    }
+}
+
+impl<T> fmt::Debug for TaskKey<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("TaskKey").field(&self.name).finish()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TaskStatus {
+    Ok,
+    Failed(String),
+    TimedOut,
+    LockTimeout,
+    Skipped(String),
+    Cancelled,
+}
+
+impl TaskStatus {
+    pub fn is_ok(&self) -> bool {
+        matches!(self, TaskStatus::Ok)
+    }
+}
+
+impl fmt::Display for TaskStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TaskStatus::Ok => write!(f, "ok"),
+            TaskStatus::Failed(e) => write!(f, "failed: {}", e),
+            TaskStatus::TimedOut => write!(f, "timed out"),
+            TaskStatus::LockTimeout => write!(f, "data lock timeout"),
+            TaskStatus::Skipped(reason) => write!(f, "skipped: {}", reason),
+            TaskStatus::Cancelled => write!(f, "cancelled by shutdown"),
+        }
+    }
+}
 
 #[derive(Clone)]
 pub struct DataSet<Output> {
-    pub timestamp: u64,
+    pub window: TimeWindow,
     pub data: HashMap<String, Output>,
+    pub status: HashMap<String, TaskStatus>,
+    pub schedule: Option<Schedule>,
+    pub skipped: Option<TimeWindow>,
 }
 
 impl<Output> DataSet<Output> {
-    pub fn new(timestamp: u64) -> Self {
+    pub fn new(window: TimeWindow) -> Self {
         DataSet {
-            timestamp,
+            window,
             data: HashMap::new(),
+            status: HashMap::new(),
+            schedule: None,
+            skipped: None,
+        }
+    }
+
+    pub fn with_schedule(mut self, schedule: Schedule) -> Self {
+        self.schedule = Some(schedule);
+        self
+    }
+
+    pub fn clone_with(&self, clone: impl Fn(&Output) -> Output) -> Self {
+        DataSet {
+            window: self.window,
+            data: self
+                .data
+                .iter()
+                .map(|(name, value)| (name.clone(), clone(value)))
+                .collect(),
+            status: self.status.clone(),
+            schedule: self.schedule.clone(),
+            skipped: self.skipped,
+        }
+    }
+
+    // Folds a later DataSet into this one. The window grows to cover both, a
+    // failed status sticks, and values present in both are combined with
+    // `merge`.
+    pub(crate) fn merge(
+        &mut self,
+        next: DataSet<Output>,
+        merge: impl Fn(&str, Output, Output) -> Output,
+    ) {
+        self.widen(&next);
+        for (name, status) in next.status {
+            if !status.is_ok() || !self.status.contains_key(&name) {
+                self.status.insert(name, status);
+            }
+        }
+        for (name, value) in next.data {
+            let merged = match self.data.remove(&name) {
+                Some(previous) => merge(&name, previous, value),
+                None => value,
+            };
+            self.data.insert(name, merged);
         }
     }
 
+    // Replaces this DataSet with a later one that it is standing in for. The
+    // window grows to cover both, and every task in `next` takes its newer
+    // status and value, even a failure over an earlier success.
+    pub(crate) fn coalesce(&mut self, next: DataSet<Output>) {
+        self.widen(&next);
+        for name in next.status.keys() {
+            self.data.remove(name);
+        }
+        self.status.extend(next.status);
+        self.data.extend(next.data);
+    }
+
+    fn widen(&mut self, next: &DataSet<Output>) {
+        self.window = TimeWindow::new(
+            self.window.start.min(next.window.start),
+            self.window.end.max(next.window.end),
+        );
+        self.skipped = match (self.skipped, next.skipped) {
+            (Some(a), Some(b)) => Some(TimeWindow::new(a.start.min(b.start), a.end.max(b.end))),
+            (a, b) => a.or(b),
+        };
+    }
+
     pub fn insert(&mut self, name: &str, data: Output) {
         self.data.insert(name.to_string(), data);
+        self.status.insert(name.to_string(), TaskStatus::Ok);
+    }
+
+    pub fn set_status(&mut self, name: &str, status: TaskStatus) {
+        self.status.insert(name.to_string(), status);
+    }
+
+    pub fn status(&self, name: &str) -> Option<&TaskStatus> {
+        self.status.get(name)
+    }
+
+    pub fn statuses(&self) -> impl Iterator<Item = (&String, &TaskStatus)> {
+        self.status.iter()
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = (&String, &TaskStatus)> {
+        self.status.iter().filter(|(_, status)| !status.is_ok())
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.status.values().all(TaskStatus::is_ok)
     }
 
     pub fn get(&self, name: &str) -> Option<&Output> {
@@ -39,22 +197,47 @@ impl<Output> DataSet<Output> {
         self.data.iter()
     }
 
-    pub fn into_iter(self) -> impl Iterator<Item = (String, Output)> {
-        self.data.into_iter()
-    }
-
     pub fn into_inner(self) -> HashMap<String, Output> {
         self.data
     }
 }
 
+impl DataSet<AnyOutput> {
+    pub fn get_typed<T: 'static>(&self, key: &TaskKey<T>) -> Option<&T> {
+        self.get(&key.name)?.downcast_ref()
+    }
+
+    pub fn take_typed<T: 'static>(&mut self, key: &TaskKey<T>) -> Option<T> {
+        let value = self.take(&key.name)?.downcast().ok()?;
+        Some(*value)
+    }
+}
+
+impl<Output> IntoIterator for DataSet<Output> {
+    type Item = (String, Output);
+    type IntoIter = std::collections::hash_map::IntoIter<String, Output>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.into_iter()
+    }
+}
+
 impl<Input: fmt::Debug> fmt::Debug for DataSet<Input> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(f, "DataSet @ {}", self.timestamp)?;
+        writeln!(f, "DataSet @ {}", self.window)?;
+        if let Some(schedule) = &self.schedule {
+            writeln!(f, "├─ Schedule: {}", schedule)?;
+        }
+        if let Some(skipped) = &self.skipped {
+            writeln!(f, "├─ Skipped: {}", skipped)?;
+        }
         writeln!(f, "├─ Items: {}", self.data.len())?;
         for (key, value) in &self.data {
             writeln!(f, "├─ {}: {:?}", key, value)?;
         }
+        for (key, status) in self.failures() {
+            writeln!(f, "├─ {}: {}", key, status)?;
+        }
         Ok(())
     }
 }
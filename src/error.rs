@@ -13,7 +13,7 @@ pub enum TaskError<Output> {
     #[error("Task Send Error: {0}")]
     TaskSendError(#[from] SendError<TaskResult<Output>>),
     #[error("DataSet Send Error: {0}")]
-    DataSetSendError(#[from] SendError<DataSet<Output>>),
+    DataSetSendError(#[from] Box<SendError<DataSet<Output>>>),
     #[error("Receive Error: {0}")]
     RecvError(#[from] RecvError),
     #[error("Join Error: {0}")]
@@ -25,3 +25,58 @@ pub enum TaskError<Output> {
     #[error("Timeout Error")]
     TimeoutError,
 }
+
+impl<Output> From<SendError<DataSet<Output>>> for TaskError<Output> {
+    fn from(e: SendError<DataSet<Output>>) -> Self {
+        TaskError::DataSetSendError(Box::new(e))
+    }
+}
+
+impl<Output> TaskError<Output> {
+    pub fn map_output<Other>(self) -> TaskError<Other> {
+        match self {
+            TaskError::TaskError(e) => TaskError::TaskError(e),
+            TaskError::TaskSendError(e) => TaskError::TaskError(e.to_string()),
+            TaskError::DataSetSendError(e) => TaskError::TaskError(e.to_string()),
+            TaskError::RecvError(e) => TaskError::RecvError(e),
+            TaskError::JoinError(e) => TaskError::JoinError(e),
+            TaskError::BroadcastError(e) => TaskError::BroadcastError(e),
+            TaskError::ShutdownError(e) => TaskError::ShutdownError(e),
+            TaskError::TimeoutError => TaskError::TimeoutError,
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum SourceError {
+    #[error("IO Error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Source Error: {0}")]
+    Other(String),
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum BuildError {
+    #[error("Dependency cycle between tasks: {}", .0.join(", "))]
+    Cycle(Vec<String>),
+    #[error("Unknown task in dependency: {0}")]
+    UnknownTask(String),
+    #[error("Task {task} and its upstream task {upstream} run on different schedules")]
+    ScheduleMismatch { task: String, upstream: String },
+    #[error("Task dependencies need an output clone, see `with_cloned_outputs`")]
+    MissingOutputClone,
+    #[error("Schedule of {0} has a zero interval")]
+    ZeroInterval(String),
+    #[error("More than one task is named {0}")]
+    DuplicateTask(String),
+    #[error("Typed task {task} is registered under the key of {key}")]
+    KeyMismatch { key: String, task: String },
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    #[error("Invalid cron expression: {0}")]
+    InvalidCron(String),
+    #[error("Invalid interval: {0}")]
+    InvalidInterval(String),
+}
@@ -0,0 +1,41 @@
+use std::collections::{HashMap, HashSet};
+
+pub type Dependencies = HashMap<String, Vec<String>>;
+
+pub(crate) fn upstream<'a>(
+    dependencies: &'a Dependencies,
+    task: &str,
+) -> impl Iterator<Item = &'a str> {
+    dependencies
+        .get(task)
+        .into_iter()
+        .flatten()
+        .map(String::as_str)
+}
+
+// Orders `tasks` into layers so every task comes after all of its upstream
+// tasks. Dependencies on tasks outside `tasks` are ignored. On a cycle, the
+// tasks that could not be ordered are returned instead.
+pub(crate) fn layers<'a>(
+    tasks: &[&'a str],
+    dependencies: &Dependencies,
+) -> Result<Vec<Vec<&'a str>>, Vec<String>> {
+    let mut remaining = tasks.to_vec();
+    let mut done: HashSet<&str> = HashSet::new();
+    let mut layers = Vec::new();
+    while !remaining.is_empty() {
+        let (ready, blocked): (Vec<&str>, Vec<&str>) = remaining.iter().partition(|task| {
+            upstream(dependencies, task).all(|up| done.contains(up) || !tasks.contains(&up))
+        });
+        if ready.is_empty() {
+            let mut cycle: Vec<String> = blocked.iter().map(|task| task.to_string()).collect();
+            cycle.sort();
+            cycle.dedup();
+            return Err(cycle);
+        }
+        done.extend(ready.iter().copied());
+        layers.push(ready);
+        remaining = blocked;
+    }
+    Ok(layers)
+}
@@ -1,45 +1,174 @@
-#[derive(Clone)]
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::error::ParseError;
+
+const MICROS_PER_MILLI: u64 = 1_000;
+const MICROS_PER_SECOND: u64 = 1_000_000;
+const MICROS_PER_MINUTE: u64 = 60 * MICROS_PER_SECOND;
+const MICROS_PER_HOUR: u64 = 60 * MICROS_PER_MINUTE;
+const MICROS_PER_DAY: u64 = 24 * MICROS_PER_HOUR;
+
+#[derive(Clone, Debug)]
 pub enum TaskInterval {
     Micros(u64),
     Millis(u64),
     Seconds(u64),
     Minutes(u64),
+    Hours(u64),
+    Days(u64),
 }
 
 impl TaskInterval {
+    // Truncates to whole seconds, like `Duration::as_secs`.
     pub fn as_secs(&self) -> u64 {
-        match self {
-            TaskInterval::Micros(micros) => *micros / 1_000_000,
-            TaskInterval::Millis(millis) => *millis / 1_000,
-            TaskInterval::Seconds(secs) => *secs,
-            TaskInterval::Minutes(mins) => mins * 60,
-        }
+        self.as_micros() / MICROS_PER_SECOND
     }
 
+    // Truncates to whole milliseconds, like `Duration::as_millis`.
     pub fn as_millis(&self) -> u64 {
-        match self {
-            TaskInterval::Micros(micros) => *micros / 1_000,
-            TaskInterval::Millis(millis) => *millis,
-            TaskInterval::Seconds(secs) => *secs * 1_000,
-            TaskInterval::Minutes(mins) => mins * 60 * 1_000,
-        }
+        self.as_micros() / MICROS_PER_MILLI
     }
 
     pub fn as_micros(&self) -> u64 {
+        let (value, unit) = self.parts();
+        value.saturating_mul(unit)
+    }
+
+    pub fn as_duration(&self) -> Duration {
+        Duration::from_micros(self.as_micros())
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.as_micros() == 0
+    }
+
+    #[deprecated(note = "the unit depends on the variant, use `as_duration` instead")]
+    pub fn as_u64(&self) -> u64 {
+        self.parts().0
+    }
+
+    fn parts(&self) -> (u64, u64) {
         match self {
-            TaskInterval::Micros(micros) => *micros,
-            TaskInterval::Millis(millis) => millis * 1_000,
-            TaskInterval::Seconds(secs) => secs * 1_000_000,
-            TaskInterval::Minutes(mins) => mins * 60 * 1_000_000,
+            TaskInterval::Micros(micros) => (*micros, 1),
+            TaskInterval::Millis(millis) => (*millis, MICROS_PER_MILLI),
+            TaskInterval::Seconds(secs) => (*secs, MICROS_PER_SECOND),
+            TaskInterval::Minutes(mins) => (*mins, MICROS_PER_MINUTE),
+            TaskInterval::Hours(hours) => (*hours, MICROS_PER_HOUR),
+            TaskInterval::Days(days) => (*days, MICROS_PER_DAY),
         }
     }
 
-    pub fn as_u64(&self) -> u64 {
+    // Picks the largest unit that represents the interval exactly.
+    fn from_micros(micros: u64) -> Self {
+        match micros {
+            0 => TaskInterval::Micros(0),
+            m if m % MICROS_PER_DAY == 0 => TaskInterval::Days(m / MICROS_PER_DAY),
+            m if m % MICROS_PER_HOUR == 0 => TaskInterval::Hours(m / MICROS_PER_HOUR),
+            m if m % MICROS_PER_MINUTE == 0 => TaskInterval::Minutes(m / MICROS_PER_MINUTE),
+            m if m % MICROS_PER_SECOND == 0 => TaskInterval::Seconds(m / MICROS_PER_SECOND),
+            m if m % MICROS_PER_MILLI == 0 => TaskInterval::Millis(m / MICROS_PER_MILLI),
+            m => TaskInterval::Micros(m),
+        }
+    }
+}
+
+// Intervals of the same length are equal whatever their unit, so
+// `Seconds(60)` and `Minutes(1)` share a cadence.
+impl PartialEq for TaskInterval {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_micros() == other.as_micros()
+    }
+}
+
+impl Eq for TaskInterval {}
+
+impl Hash for TaskInterval {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_micros().hash(state);
+    }
+}
+
+// Sub-microsecond precision is dropped.
+impl From<Duration> for TaskInterval {
+    fn from(duration: Duration) -> Self {
+        TaskInterval::from_micros(u64::try_from(duration.as_micros()).unwrap_or(u64::MAX))
+    }
+}
+
+impl From<TaskInterval> for Duration {
+    fn from(interval: TaskInterval) -> Self {
+        interval.as_duration()
+    }
+}
+
+impl fmt::Display for TaskInterval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            TaskInterval::Micros(micros) => *micros,
-            TaskInterval::Millis(millis) => *millis,
-            TaskInterval::Seconds(secs) => *secs,
-            TaskInterval::Minutes(mins) => *mins,
+            TaskInterval::Micros(micros) => write!(f, "{}us", micros),
+            TaskInterval::Millis(millis) => write!(f, "{}ms", millis),
+            TaskInterval::Seconds(secs) => write!(f, "{}s", secs),
+            TaskInterval::Minutes(mins) => write!(f, "{}m", mins),
+            TaskInterval::Hours(hours) => write!(f, "{}h", hours),
+            TaskInterval::Days(days) => write!(f, "{}d", days),
+        }
+    }
+}
+
+// Accepts one or more <number><unit> parts, e.g. "250ms", "5s" or "1h30m".
+impl FromStr for TaskInterval {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| ParseError::InvalidInterval(format!("{} in '{}'", reason, s));
+        let mut rest = s.trim();
+        if rest.is_empty() {
+            return Err(invalid("empty interval"));
+        }
+
+        let mut total: u64 = 0;
+        while !rest.is_empty() {
+            let digits = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            if digits == 0 {
+                return Err(invalid("expected a number"));
+            }
+            let value: u64 = rest[..digits]
+                .parse()
+                .map_err(|_| invalid("number too large"))?;
+            rest = &rest[digits..];
+
+            let units = rest
+                .find(|c: char| c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            let unit = match &rest[..units] {
+                "us" | "µs" => 1,
+                "ms" => MICROS_PER_MILLI,
+                "s" => MICROS_PER_SECOND,
+                "m" => MICROS_PER_MINUTE,
+                "h" => MICROS_PER_HOUR,
+                "d" => MICROS_PER_DAY,
+                "" => return Err(invalid("missing unit")),
+                unit => return Err(invalid(&format!("unknown unit '{}'", unit))),
+            };
+            rest = &rest[units..];
+
+            total = value
+                .checked_mul(unit)
+                .and_then(|micros| total.checked_add(micros))
+                .ok_or_else(|| invalid("interval too large"))?;
+        }
+
+        if total == 0 {
+            return Err(invalid("interval must be greater than zero"));
+        }
+        // Schedules work in signed microseconds.
+        if total > i64::MAX as u64 {
+            return Err(invalid("interval too large"));
         }
+        Ok(TaskInterval::from_micros(total))
     }
 }
@@ -1,26 +1,61 @@
+mod clock;
 mod config;
 mod context;
+mod cron;
 mod data_types;
 mod error;
+mod graph;
 mod interval;
+mod pool;
+mod queue;
+mod rollup;
 mod runner;
+mod schedule;
+mod source;
+mod stateful;
+mod subscribe;
 mod task;
 mod tests;
+mod window;
 
-pub use config::RunnerConfig;
+pub use clock::{Clock, ManualClock, SystemClock, TokioClock};
+pub use config::{
+    BackpressurePolicy, ExecutionMode, FailurePolicy, MissedTickPolicy, MissingResultPolicy,
+    RunnerConfig, TaskConfig,
+};
 pub use context::{Context, ContextBuilder};
-pub use data_types::DataSet;
-pub use error::TaskError;
+pub use cron::CronSchedule;
+pub use data_types::{AnyOutput, DataSet, TaskKey, TaskStatus};
+pub use error::{BuildError, ParseError, SourceError, TaskError};
 pub use interval::TaskInterval;
-pub use runner::Runner;
-pub use task::Runnable;
+pub use pool::TaskPool;
+pub use queue::{data_channel, DataReceiver, DataSender, Delivery};
+pub use rollup::Rollup;
+pub use runner::{Runner, RunnerHandle};
+pub use schedule::{Schedule, TickTiming};
+pub use source::{ChannelSource, DataSource};
+pub use stateful::{Stateful, StatefulRunnable};
+pub use task::{AsyncRunnable, BoxFuture, Runnable, Task, TaskSpec};
+pub use window::TimeWindow;
 
 pub mod prelude {
-    pub use crate::config::RunnerConfig;
+    pub use crate::clock::{Clock, ManualClock, SystemClock, TokioClock};
+    pub use crate::config::{
+        BackpressurePolicy, ExecutionMode, FailurePolicy, MissedTickPolicy, MissingResultPolicy,
+        RunnerConfig, TaskConfig,
+    };
     pub use crate::context::{Context, ContextBuilder};
-    pub use crate::data_types::DataSet;
-    pub use crate::error::TaskError;
+    pub use crate::cron::CronSchedule;
+    pub use crate::data_types::{AnyOutput, DataSet, TaskKey, TaskStatus};
+    pub use crate::error::{BuildError, ParseError, SourceError, TaskError};
     pub use crate::interval::TaskInterval;
-    pub use crate::runner::Runner;
-    pub use crate::task::Runnable;
+    pub use crate::pool::TaskPool;
+    pub use crate::queue::{data_channel, DataReceiver, DataSender, Delivery};
+    pub use crate::rollup::Rollup;
+    pub use crate::runner::{Runner, RunnerHandle};
+    pub use crate::schedule::{Schedule, TickTiming};
+    pub use crate::source::{ChannelSource, DataSource};
+    pub use crate::stateful::{Stateful, StatefulRunnable};
+    pub use crate::task::{AsyncRunnable, BoxFuture, Runnable, Task, TaskSpec};
+    pub use crate::window::TimeWindow;
 }
@@ -0,0 +1,200 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use tokio::sync::mpsc::error::{SendError, TryRecvError};
+use tokio::sync::Notify;
+
+use crate::config::BackpressurePolicy;
+use crate::data_types::DataSet;
+use crate::window::TimeWindow;
+
+// The bounded queue DataSets are delivered on. Unlike an mpsc channel, a
+// full queue can give way at either end, as the `BackpressurePolicy` says.
+pub fn data_channel<Output>(
+    capacity: usize,
+    policy: BackpressurePolicy,
+) -> (DataSender<Output>, DataReceiver<Output>) {
+    let shared = Arc::new(Shared {
+        state: Mutex::new(State {
+            items: VecDeque::new(),
+            senders: 1,
+            receiver: true,
+        }),
+        items: Notify::new(),
+        space: Notify::new(),
+        capacity: capacity.max(1),
+        policy,
+    });
+    let sender = DataSender {
+        shared: Arc::clone(&shared),
+    };
+    (sender, DataReceiver { shared })
+}
+
+struct State<Output> {
+    items: VecDeque<DataSet<Output>>,
+    senders: usize,
+    receiver: bool,
+}
+
+struct Shared<Output> {
+    state: Mutex<State<Output>>,
+    items: Notify,
+    space: Notify,
+    capacity: usize,
+    policy: BackpressurePolicy,
+}
+
+impl<Output> Shared<Output> {
+    // Nothing holds the lock across a panic point, so a poisoned lock still
+    // has a consistent queue.
+    fn lock(&self) -> MutexGuard<'_, State<Output>> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+// What a full queue gave up to make room.
+pub enum Delivery<Output> {
+    Queued,
+    Dropped(DataSet<Output>),
+    Coalesced(TimeWindow),
+}
+
+pub struct DataSender<Output> {
+    shared: Arc<Shared<Output>>,
+}
+
+impl<Output> DataSender<Output> {
+    pub async fn send(
+        &self,
+        dataset: DataSet<Output>,
+    ) -> Result<Delivery<Output>, SendError<DataSet<Output>>> {
+        let shared = &self.shared;
+        loop {
+            let space = shared.space.notified();
+            tokio::pin!(space);
+            space.as_mut().enable();
+            {
+                let mut state = shared.lock();
+                if !state.receiver {
+                    return Err(SendError(dataset));
+                }
+                if state.items.len() < shared.capacity {
+                    state.items.push_back(dataset);
+                    shared.items.notify_one();
+                    return Ok(Delivery::Queued);
+                }
+                if shared.policy != BackpressurePolicy::Block {
+                    return Ok(Self::make_room(&mut state.items, dataset, shared.policy));
+                }
+            }
+            space.await;
+        }
+    }
+
+    fn make_room(
+        items: &mut VecDeque<DataSet<Output>>,
+        dataset: DataSet<Output>,
+        policy: BackpressurePolicy,
+    ) -> Delivery<Output> {
+        if policy == BackpressurePolicy::DropNewest {
+            return Delivery::Dropped(dataset);
+        }
+        if policy == BackpressurePolicy::Coalesce {
+            // Folded into the newest DataSet of the same cadence.
+            if let Some(newest) = items
+                .iter_mut()
+                .rev()
+                .find(|queued| queued.schedule == dataset.schedule)
+            {
+                newest.coalesce(dataset);
+                return Delivery::Coalesced(newest.window);
+            }
+        }
+        let oldest = items.pop_front();
+        items.push_back(dataset);
+        match oldest {
+            Some(oldest) => Delivery::Dropped(oldest),
+            None => Delivery::Queued,
+        }
+    }
+
+    pub fn is_closed(&self) -> bool {
+        !self.shared.lock().receiver
+    }
+}
+
+impl<Output> Clone for DataSender<Output> {
+    fn clone(&self) -> Self {
+        self.shared.lock().senders += 1;
+        DataSender {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl<Output> Drop for DataSender<Output> {
+    fn drop(&mut self) {
+        let mut state = self.shared.lock();
+        state.senders -= 1;
+        if state.senders == 0 {
+            self.shared.items.notify_one();
+        }
+    }
+}
+
+pub struct DataReceiver<Output> {
+    shared: Arc<Shared<Output>>,
+}
+
+impl<Output> DataReceiver<Output> {
+    // Resolves with `None` once every sender is gone and the queue is empty.
+    pub async fn recv(&mut self) -> Option<DataSet<Output>> {
+        let shared = &self.shared;
+        loop {
+            let items = shared.items.notified();
+            tokio::pin!(items);
+            items.as_mut().enable();
+            {
+                let mut state = shared.lock();
+                if let Some(dataset) = state.items.pop_front() {
+                    shared.space.notify_one();
+                    return Some(dataset);
+                }
+                if state.senders == 0 {
+                    return None;
+                }
+            }
+            items.await;
+        }
+    }
+
+    pub fn try_recv(&mut self) -> Result<DataSet<Output>, TryRecvError> {
+        let mut state = self.shared.lock();
+        match state.items.pop_front() {
+            Some(dataset) => {
+                self.shared.space.notify_one();
+                Ok(dataset)
+            }
+            None if state.senders == 0 => Err(TryRecvError::Disconnected),
+            None => Err(TryRecvError::Empty),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.shared.lock().items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<Output> Drop for DataReceiver<Output> {
+    fn drop(&mut self) {
+        self.shared.lock().receiver = false;
+        self.shared.space.notify_waiters();
+    }
+}
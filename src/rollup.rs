@@ -0,0 +1,183 @@
+use std::collections::{HashMap, VecDeque};
+
+use tracing::{debug, warn};
+
+use crate::data_types::DataSet;
+use crate::queue::DataReceiver;
+use crate::schedule::Schedule;
+use crate::window::TimeWindow;
+
+type Merge<Output> = Box<dyn Fn(Output, Output) -> Output + Send + Sync>;
+
+// Rolls up consecutive DataSets into coarser windows, e.g. twelve 5s
+// DataSets into a 1m one. Coarse windows follow wall-clock boundaries and
+// keep their size; each DataSet goes to the coarse window its start falls in.
+// A DataSet that straddles a boundary is rejected, and the time it covered is
+// reported as `skipped` on the coarse windows on either side. A rollup follows
+// a single source cadence; DataSets of other cadences are skipped.
+pub struct Rollup<Output> {
+    receiver: DataReceiver<Output>,
+    schedule: Schedule,
+    // The cadence rolled up, fixed by the first DataSet unless set up front.
+    source: Option<Option<Schedule>>,
+    merges: HashMap<String, Merge<Output>>,
+    default_merge: Option<Merge<Output>>,
+    current: Option<DataSet<Output>>,
+    ready: VecDeque<DataSet<Output>>,
+    // The part of a rejected DataSet that reaches into the next coarse window.
+    gap: Option<TimeWindow>,
+    rejected: u64,
+}
+
+impl<Output> Rollup<Output> {
+    pub fn new(receiver: DataReceiver<Output>, schedule: impl Into<Schedule>) -> Self {
+        let schedule = match schedule.into() {
+            Schedule::Interval(interval) => Schedule::Aligned(interval),
+            schedule => schedule,
+        };
+        Rollup {
+            receiver,
+            schedule,
+            source: None,
+            merges: HashMap::new(),
+            default_merge: None,
+            current: None,
+            ready: VecDeque::new(),
+            gap: None,
+            rejected: 0,
+        }
+    }
+
+    pub fn with_source(mut self, schedule: impl Into<Schedule>) -> Self {
+        self.source = Some(Some(schedule.into()));
+        self
+    }
+
+    // `merge` gets the rolled-up value so far and the next one. Tasks
+    // without a merge keep their latest value.
+    pub fn with_merge(
+        mut self,
+        name: &str,
+        merge: impl Fn(Output, Output) -> Output + Send + Sync + 'static,
+    ) -> Self {
+        self.merges.insert(name.to_string(), Box::new(merge));
+        self
+    }
+
+    pub fn with_default_merge(
+        mut self,
+        merge: impl Fn(Output, Output) -> Output + Send + Sync + 'static,
+    ) -> Self {
+        self.default_merge = Some(Box::new(merge));
+        self
+    }
+
+    pub fn rejected_datasets(&self) -> u64 {
+        self.rejected
+    }
+
+    // Resolves with the next complete coarse window. A window that is still
+    // incomplete is emitted as soon as a later one starts, and once the
+    // runner closes the channel.
+    pub async fn recv(&mut self) -> Option<DataSet<Output>> {
+        loop {
+            if let Some(dataset) = self.ready.pop_front() {
+                return Some(dataset);
+            }
+            match self.receiver.recv().await {
+                Some(dataset) => self.push(dataset),
+                None => return self.current.take(),
+            }
+        }
+    }
+
+    fn push(&mut self, dataset: DataSet<Output>) {
+        let source = self.source.get_or_insert_with(|| dataset.schedule.clone());
+        if *source != dataset.schedule {
+            debug!(
+                window = %dataset.window,
+                "Skipping dataset from another cadence than the rollup's source"
+            );
+            return;
+        }
+        let window = self.window_for(&dataset.window);
+        let current = match self.current.take() {
+            Some(current) if current.window == window => Some(current),
+            previous => {
+                if let Some(previous) = previous {
+                    warn!(window = %previous.window, "Emitting incomplete rollup");
+                    self.ready.push_back(previous);
+                }
+                None
+            }
+        };
+
+        if dataset.window.end > window.end {
+            self.rejected += 1;
+            warn!(
+                window = %dataset.window,
+                boundary = %window.end,
+                rejected = %self.rejected,
+                "Rejecting dataset that straddles a rollup boundary"
+            );
+            // Nothing later can fall in this coarse window any more.
+            if let Some(mut current) = current {
+                add_skipped(
+                    &mut current,
+                    TimeWindow::new(dataset.window.start, window.end),
+                );
+                debug!(window = %current.window, "Rollup complete");
+                self.ready.push_back(current);
+            }
+            self.gap = Some(TimeWindow::new(window.end, dataset.window.end));
+            return;
+        }
+
+        let ends = dataset.window.end == window.end;
+        let merged = match current {
+            Some(current) => self.merge(current, dataset),
+            None => {
+                let mut dataset = dataset;
+                dataset.window = window;
+                dataset.schedule = Some(self.schedule.clone());
+                if let Some(gap) = self.gap.take().filter(|gap| gap.start == window.start) {
+                    add_skipped(&mut dataset, gap);
+                }
+                dataset
+            }
+        };
+        if ends {
+            debug!(window = %merged.window, "Rollup complete");
+            self.ready.push_back(merged);
+        } else {
+            self.current = Some(merged);
+        }
+    }
+
+    fn window_for(&self, window: &TimeWindow) -> TimeWindow {
+        match self.schedule.next_after(window.start) {
+            Some(end) => self.schedule.window_ending(end),
+            None => *window,
+        }
+    }
+
+    // `DataSet::merge` widens the window, so the coarse one is put back.
+    fn merge(&self, mut current: DataSet<Output>, next: DataSet<Output>) -> DataSet<Output> {
+        let window = current.window;
+        current.merge(next, |name, previous, value| {
+            match self.merges.get(name).or(self.default_merge.as_ref()) {
+                Some(merge) => merge(previous, value),
+                None => value,
+            }
+        });
+        current.window = window;
+        current
+    }
+}
+
+fn add_skipped<Output>(dataset: &mut DataSet<Output>, gap: TimeWindow) {
+    dataset.skipped = Some(match dataset.skipped {
+        Some(skipped) => TimeWindow::new(skipped.start.min(gap.start), skipped.end.max(gap.end)),
+        None => gap,
+    });
+}
@@ -1,148 +1,1397 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::task::Poll;
 use std::time::Duration;
 
-use tokio::sync::{broadcast, mpsc};
-use tokio::time::interval;
-use tracing::{debug, error, info, instrument, warn};
+use chrono::{DateTime, Utc};
+use tokio::sync::broadcast::error::{RecvError, TryRecvError};
+use tokio::sync::{broadcast, mpsc, watch, Mutex, RwLock};
+use tokio::task::JoinHandle;
+use tokio::time::{self, error::Elapsed, Instant};
+use tracing::{debug, error, info, info_span, instrument, warn, Instrument, Span};
 
+use crate::clock::Clock;
+use crate::config::{
+    BackpressurePolicy, FailurePolicy, MissedTickPolicy, MissingResultPolicy, TaskConfig,
+};
 use crate::context::Context;
-use crate::data_types::DataSet;
+use crate::data_types::{DataSet, TaskStatus};
 use crate::error::TaskError;
-use crate::interval::TaskInterval;
-use crate::task::{TaskContext, TaskResult, Worker};
+use crate::graph;
+use crate::queue::Delivery;
+use crate::schedule::{Schedule, TickTiming, Ticker};
+use crate::subscribe::{LatestDataSet, Subscribers, Subscription};
+use crate::task::{
+    AsyncRunnable, BoxFuture, Runnable, Task, TaskContext, TaskResult, TaskSpec, Tick, Worker,
+};
+use crate::window::TimeWindow;
+
+enum TaskChange<Input, Output> {
+    Add(TaskSpec<Input, Output>),
+    Remove(String),
+    Replace(String, TaskSpec<Input, Output>),
+}
+
+enum Command<Input, Output> {
+    Change(TaskChange<Input, Output>),
+    Pause,
+    Resume,
+    RunNow(Option<TimeWindow>),
+}
+
+// Counts the DataSets a full receiver cost, shared with every handle.
+#[derive(Default)]
+struct DeliveryStats {
+    dropped: AtomicU64,
+    coalesced: AtomicU64,
+}
+
+pub struct RunnerHandle<Input, Output> {
+    control: mpsc::UnboundedSender<Command<Input, Output>>,
+    shutdown: broadcast::Sender<ShutdownMode>,
+    subscribers: Arc<Subscribers<Output>>,
+    delivery: Arc<DeliveryStats>,
+}
+
+impl<Input, Output> Clone for RunnerHandle<Input, Output> {
+    fn clone(&self) -> Self {
+        RunnerHandle {
+            control: self.control.clone(),
+            shutdown: self.shutdown.clone(),
+            subscribers: Arc::clone(&self.subscribers),
+            delivery: Arc::clone(&self.delivery),
+        }
+    }
+}
+
+impl<Input: 'static, Output: 'static> RunnerHandle<Input, Output> {
+    pub fn add_task(
+        &self,
+        task: impl Runnable<Input, Output> + 'static,
+    ) -> Result<(), TaskError<Output>> {
+        self.add_task_spec(TaskSpec::new(
+            Task::Sync(Arc::new(task)),
+            TaskConfig::default(),
+        ))
+    }
+
+    pub fn add_async_task(
+        &self,
+        task: impl AsyncRunnable<Input, Output> + 'static,
+    ) -> Result<(), TaskError<Output>> {
+        self.add_task_spec(TaskSpec::new(
+            Task::Async(Arc::new(task)),
+            TaskConfig::default(),
+        ))
+    }
+
+    pub fn add_task_spec(&self, spec: TaskSpec<Input, Output>) -> Result<(), TaskError<Output>> {
+        self.send(Command::Change(TaskChange::Add(spec)))
+    }
+
+    pub fn remove_task(&self, name: &str) -> Result<(), TaskError<Output>> {
+        self.send(Command::Change(TaskChange::Remove(name.to_string())))
+    }
+
+    pub fn replace_task(
+        &self,
+        name: &str,
+        task: impl Runnable<Input, Output> + 'static,
+    ) -> Result<(), TaskError<Output>> {
+        self.replace_task_spec(
+            name,
+            TaskSpec::new(Task::Sync(Arc::new(task)), TaskConfig::default()),
+        )
+    }
+
+    pub fn replace_task_spec(
+        &self,
+        name: &str,
+        spec: TaskSpec<Input, Output>,
+    ) -> Result<(), TaskError<Output>> {
+        self.send(Command::Change(TaskChange::Replace(name.to_string(), spec)))
+    }
+
+    pub fn pause(&self) -> Result<(), TaskError<Output>> {
+        self.send(Command::Pause)
+    }
+
+    pub fn resume(&self) -> Result<(), TaskError<Output>> {
+        self.send(Command::Resume)
+    }
+
+    pub fn run_now(&self, window: Option<TimeWindow>) -> Result<(), TaskError<Output>> {
+        self.send(Command::RunNow(window))
+    }
+
+    pub fn subscribe(
+        &self,
+        capacity: usize,
+        policy: BackpressurePolicy,
+    ) -> Result<Subscription<Output>, TaskError<Output>> {
+        self.subscribers.subscribe(capacity, policy)
+    }
+
+    pub fn watch(&self) -> Result<LatestDataSet<Output>, TaskError<Output>> {
+        self.subscribers.watch()
+    }
+
+    pub fn dropped_datasets(&self) -> u64 {
+        self.delivery.dropped.load(Ordering::Relaxed)
+    }
+
+    pub fn coalesced_datasets(&self) -> u64 {
+        self.delivery.coalesced.load(Ordering::Relaxed)
+    }
+
+    pub fn shutdown(&self) -> Result<(), TaskError<Output>> {
+        send_shutdown(&self.shutdown, ShutdownMode::Graceful)
+    }
+
+    pub fn abort(&self) -> Result<(), TaskError<Output>> {
+        send_shutdown(&self.shutdown, ShutdownMode::Abort)
+    }
+
+    fn send(&self, command: Command<Input, Output>) -> Result<(), TaskError<Output>> {
+        self.control
+            .send(command)
+            .map_err(|_| TaskError::TaskError("Runner control channel closed".to_string()))
+    }
+}
+
+struct WorkerSlot<Input, Output> {
+    name: String,
+    // Told when the runner gives up on one of the worker's results.
+    task: Task<Input, Output>,
+    cadence: usize,
+    deadline: Option<Duration>,
+    abort_on_failure: bool,
+    handle: JoinHandle<Result<(), TaskError<Output>>>,
+}
+
+// Each cadence ticks on its own lane, with its own tick broadcast and result
+// channel, so a slow cadence never holds up the others.
+struct Lane<Input, Output> {
+    ticker: Ticker,
+    broadcaster: broadcast::Sender<Tick<Input, Output>>,
+    results: mpsc::Sender<TaskResult<Output>>,
+    // Taken by the tick in flight on this lane and handed back when it ends.
+    receiver: Option<mpsc::Receiver<TaskResult<Output>>>,
+}
+
+struct WorkerSet<Input, Output> {
+    data: Arc<RwLock<Input>>,
+    lock_timeout: Duration,
+    slots: Vec<WorkerSlot<Input, Output>>,
+    lanes: Vec<Lane<Input, Output>>,
+    broadcast_capacity: usize,
+    results_capacity: usize,
+    clock: Arc<dyn Clock>,
+    missed_ticks: MissedTickPolicy,
+    timing: TickTiming,
+}
+
+impl<Input: Send + Sync + 'static, Output: Send + Sync + 'static> WorkerSet<Input, Output> {
+    fn spawn(&mut self, spec: TaskSpec<Input, Output>) {
+        let name = spec.task.name();
+        let cadence = self.cadence(spec.config.schedule.as_ref());
+        debug!(task_name = %name, cadence = %cadence, "Spawning worker task");
+        let slot = WorkerSlot {
+            deadline: spec.config.deadline(),
+            abort_on_failure: matches!(spec.config.on_failure, FailurePolicy::Abort),
+            task: spec.task.clone(),
+            name,
+            cadence,
+            handle: self.spawn_worker(spec, cadence),
+        };
+        self.slots.push(slot);
+    }
+
+    // Tasks sharing a schedule share a ticker; the first cadence is the
+    // context's own schedule.
+    fn cadence(&mut self, schedule: Option<&Schedule>) -> usize {
+        match (self.find_cadence(schedule), schedule) {
+            (Some(cadence), _) => cadence,
+            (None, Some(schedule)) => {
+                if schedule.is_zero() {
+                    warn!(schedule = %schedule, "Task schedule has a zero interval and never fires");
+                }
+                info!(schedule = %schedule, "Adding task cadence");
+                self.add_cadence(schedule.clone());
+                self.lanes.len() - 1
+            }
+            (None, None) => 0,
+        }
+    }
+
+    fn find_cadence(&self, schedule: Option<&Schedule>) -> Option<usize> {
+        let Some(schedule) = schedule else {
+            return Some(0);
+        };
+        self.lanes
+            .iter()
+            .position(|lane| lane.ticker.schedule() == schedule)
+    }
+
+    fn add_cadence(&mut self, schedule: Schedule) {
+        let ticker = Ticker::new(
+            schedule,
+            Arc::clone(&self.clock),
+            self.missed_ticks,
+            self.timing,
+        );
+        let (broadcaster, _) = broadcast::channel(self.broadcast_capacity);
+        let (results, receiver) = mpsc::channel(self.results_capacity);
+        self.lanes.push(Lane {
+            ticker,
+            broadcaster,
+            results,
+            receiver: Some(receiver),
+        });
+    }
+
+    fn spawn_worker(
+        &self,
+        spec: TaskSpec<Input, Output>,
+        cadence: usize,
+    ) -> JoinHandle<Result<(), TaskError<Output>>> {
+        let lane = &self.lanes[cadence];
+        let task_ctx = TaskContext {
+            data: Arc::clone(&self.data),
+            receiver: lane.broadcaster.subscribe(),
+            sender: lane.results.clone(),
+            cadence,
+        };
+        let mut worker = Worker::new(spec, task_ctx);
+        let lock_timeout = self.lock_timeout;
+        tokio::spawn(async move { worker.run(lock_timeout).await })
+    }
+
+    fn remove(&mut self, name: &str) -> bool {
+        let before = self.slots.len();
+        self.slots.retain(|slot| {
+            let keep = slot.name != name;
+            if !keep {
+                slot.handle.abort();
+            }
+            keep
+        });
+        before != self.slots.len()
+    }
+
+    fn contains(&self, name: &str) -> bool {
+        self.slots.iter().any(|slot| slot.name == name)
+    }
+
+    // Results are collected by task name, so a change that would leave two
+    // tasks with the same name is skipped.
+    fn apply(&mut self, change: TaskChange<Input, Output>) {
+        match change {
+            TaskChange::Add(spec) => {
+                let name = spec.task.name();
+                if self.contains(&name) {
+                    warn!(task_name = %name, "Task already exists, skipping add");
+                    return;
+                }
+                info!(task_name = %name, "Adding task");
+                self.spawn(spec);
+            }
+            TaskChange::Remove(name) => {
+                if self.remove(&name) {
+                    info!(task_name = %name, "Removed task");
+                } else {
+                    warn!(task_name = %name, "Cannot remove unknown task");
+                }
+            }
+            TaskChange::Replace(name, spec) => {
+                let replacement = spec.task.name();
+                if replacement != name && self.contains(&replacement) {
+                    warn!(
+                        task_name = %name,
+                        replacement = %replacement,
+                        "Replacement task already exists, skipping replace"
+                    );
+                    return;
+                }
+                if !self.remove(&name) {
+                    warn!(task_name = %name, "Replacing unknown task, adding it instead");
+                }
+                info!(task_name = %name, replacement = %replacement, "Replaced task");
+                self.spawn(spec);
+            }
+        }
+    }
+
+    // A change waits while a tick is in flight on any cadence it touches, so
+    // the tick never waits on a worker that has been removed under it.
+    fn is_blocked(&self, change: &TaskChange<Input, Output>) -> bool {
+        let busy = |cadence| self.is_busy(cadence);
+        let removes = |name: &str| {
+            self.slots
+                .iter()
+                .any(|slot| slot.name == name && busy(slot.cadence))
+        };
+        let adds = |spec: &TaskSpec<Input, Output>| {
+            self.find_cadence(spec.config.schedule.as_ref())
+                .is_some_and(busy)
+        };
+        match change {
+            TaskChange::Add(spec) => adds(spec),
+            TaskChange::Remove(name) => removes(name),
+            TaskChange::Replace(name, spec) => removes(name) || adds(spec),
+        }
+    }
+
+    fn expected(&self, cadence: usize) -> Vec<(String, Option<Duration>)> {
+        self.slots
+            .iter()
+            .filter(|slot| slot.cadence == cadence)
+            .map(|slot| (slot.name.clone(), slot.deadline))
+            .collect()
+    }
+
+    fn tasks(&self, cadence: usize) -> HashMap<String, Task<Input, Output>> {
+        self.slots
+            .iter()
+            .filter(|slot| slot.cadence == cadence)
+            .map(|slot| (slot.name.clone(), slot.task.clone()))
+            .collect()
+    }
+
+    fn abort_on_failure(&self, cadence: usize) -> impl Iterator<Item = &str> {
+        self.slots
+            .iter()
+            .filter(move |slot| slot.cadence == cadence && slot.abort_on_failure)
+            .map(|slot| slot.name.as_str())
+    }
+
+    fn is_idle(&self, cadence: usize) -> bool {
+        cadence != 0 && self.slots.iter().all(|slot| slot.cadence != cadence)
+    }
+
+    fn is_busy(&self, cadence: usize) -> bool {
+        self.lanes[cadence].receiver.is_none()
+    }
+
+    // Resolves with the first cadence due, skipping cadences that still have
+    // a tick in flight. Dropping it is safe since each ticker keeps its own
+    // position.
+    async fn next_tick(&mut self) -> (usize, TimeWindow, Option<TimeWindow>) {
+        let mut ticks: Vec<_> = self
+            .lanes
+            .iter_mut()
+            .enumerate()
+            .filter(|(_, lane)| lane.receiver.is_some())
+            .map(|(cadence, lane)| (cadence, Box::pin(lane.ticker.tick())))
+            .collect();
+        std::future::poll_fn(|cx| {
+            for (cadence, tick) in ticks.iter_mut() {
+                if let Poll::Ready((window, skipped)) = tick.as_mut().poll(cx) {
+                    return Poll::Ready((*cadence, window, skipped));
+                }
+            }
+            Poll::Pending
+        })
+        .await
+    }
+
+    // Waits for the workers until the shutdown deadline, aborting whatever is
+    // still running once it passes or an abort arrives.
+    async fn join(self, shutdown: &mut ShutdownSignal) -> Result<(), TaskError<Output>> {
+        let WorkerSet { lanes, slots, .. } = self;
+        drop(lanes);
+        let deadline = shutdown.deadline();
+        let mut slots = slots.into_iter();
+        while let Some(mut slot) = slots.next() {
+            tokio::select! {
+                result = &mut slot.handle => result??,
+                _ = time::sleep_until(deadline) => {
+                    warn!(task_name = %slot.name, "Worker did not stop in time, aborting");
+                    slot.handle.abort();
+                }
+                _ = shutdown.recv() => {
+                    warn!("Aborting worker tasks");
+                    slot.handle.abort();
+                    for slot in slots {
+                        slot.handle.abort();
+                    }
+                    break;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn abort(self) {
+        for slot in self.slots {
+            slot.handle.abort();
+        }
+    }
+}
+
+// Push sources run for as long as the runner does, however `run` returns.
+struct Feeds(Vec<JoinHandle<()>>);
+
+impl Drop for Feeds {
+    fn drop(&mut self) {
+        for feed in &self.0 {
+            feed.abort();
+        }
+    }
+}
+
+struct TickState<Input, Output> {
+    workers: WorkerSet<Input, Output>,
+    pending: Vec<TaskChange<Input, Output>>,
+    last_good: HashMap<String, Output>,
+    source_failures: Vec<(String, TaskStatus)>,
+    tick_id: u64,
+}
+
+// A tick running on its lane. It owns the lane's result receiver until it
+// ends, then hands it back with the collected dataset.
+struct InFlightTick<Input, Output> {
+    id: u64,
+    cadence: usize,
+    broadcaster: broadcast::Sender<Tick<Input, Output>>,
+    results: mpsc::Receiver<TaskResult<Output>>,
+    shutdown: watch::Receiver<Option<Stop>>,
+    dataset: DataSet<Output>,
+    expected: Vec<(String, Option<Duration>)>,
+    tasks: HashMap<String, Task<Input, Output>>,
+}
+
+type TickOutcome<Input, Output> = (
+    InFlightTick<Input, Output>,
+    Result<Option<ShutdownMode>, TaskError<Output>>,
+);
+
+type TickFuture<'a, Input, Output> = BoxFuture<'a, TickOutcome<Input, Output>>;
+
+#[derive(Clone, Copy, Debug)]
+enum ShutdownMode {
+    Graceful,
+    Abort,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Stop {
+    mode: ShutdownMode,
+    deadline: Instant,
+}
+
+// Tracks a shutdown in progress. The first graceful signal fixes a single
+// deadline that covers both draining the ticks in flight and joining the
+// workers; a later abort still cuts the drain short. Ticks in flight follow
+// it through `watch`.
+struct ShutdownSignal {
+    receiver: broadcast::Receiver<ShutdownMode>,
+    timeout: Duration,
+    stop: watch::Sender<Option<Stop>>,
+}
+
+impl ShutdownSignal {
+    fn new(receiver: broadcast::Receiver<ShutdownMode>, timeout: Duration) -> Self {
+        ShutdownSignal {
+            receiver,
+            timeout,
+            stop: watch::Sender::new(None),
+        }
+    }
+
+    // Records a received signal, returning the mode if it changes anything.
+    fn update(&mut self, mode: ShutdownMode) -> Option<ShutdownMode> {
+        let deadline = match (self.mode(), mode) {
+            (Some(ShutdownMode::Abort), _)
+            | (Some(ShutdownMode::Graceful), ShutdownMode::Graceful) => return None,
+            (_, ShutdownMode::Abort) => Instant::now(),
+            (None, ShutdownMode::Graceful) => Instant::now() + self.timeout,
+        };
+        self.stop.send_replace(Some(Stop { mode, deadline }));
+        Some(mode)
+    }
+
+    // Waits for the next signal that changes the shutdown state. A lagged
+    // receiver counts as graceful; an abort that overwrote it is still queued.
+    async fn recv(&mut self) -> ShutdownMode {
+        loop {
+            let mode = match self.receiver.recv().await {
+                Ok(mode) => mode,
+                Err(RecvError::Lagged(_)) => ShutdownMode::Graceful,
+                Err(RecvError::Closed) => std::future::pending().await,
+            };
+            if let Some(mode) = self.update(mode) {
+                return mode;
+            }
+        }
+    }
+
+    fn try_recv(&mut self) -> Option<ShutdownMode> {
+        loop {
+            let mode = match self.receiver.try_recv() {
+                Ok(mode) => mode,
+                Err(TryRecvError::Lagged(_)) => ShutdownMode::Graceful,
+                Err(_) => return self.mode(),
+            };
+            self.update(mode);
+        }
+    }
+
+    fn watch(&self) -> watch::Receiver<Option<Stop>> {
+        self.stop.subscribe()
+    }
+
+    fn mode(&self) -> Option<ShutdownMode> {
+        self.stop.borrow().map(|stop| stop.mode)
+    }
+
+    fn deadline(&self) -> Instant {
+        self.stop
+            .borrow()
+            .map_or_else(|| Instant::now() + self.timeout, |stop| stop.deadline)
+    }
+}
 
 pub struct Runner<Input, Output> {
     pub ctx: Context<Input, Output>,
-    shutdown: broadcast::Sender<()>,
+    shutdown: broadcast::Sender<ShutdownMode>,
+    control: mpsc::UnboundedSender<Command<Input, Output>>,
+    commands: Mutex<mpsc::UnboundedReceiver<Command<Input, Output>>>,
+    subscribers: Arc<Subscribers<Output>>,
+    delivery: Arc<DeliveryStats>,
 }
 
 impl<Input: Send + Sync + 'static, Output: Send + Sync + 'static> Runner<Input, Output> {
     pub fn new(ctx: Context<Input, Output>) -> Self {
         let (shutdown, _) = broadcast::channel(1);
-        Runner { ctx, shutdown }
+        let (control, commands) = mpsc::unbounded_channel();
+        let subscribers = Arc::new(Subscribers::new(ctx.output_clone.clone()));
+        Runner {
+            ctx,
+            shutdown,
+            control,
+            commands: Mutex::new(commands),
+            subscribers,
+            delivery: Arc::default(),
+        }
+    }
+
+    pub fn handle(&self) -> RunnerHandle<Input, Output> {
+        RunnerHandle {
+            control: self.control.clone(),
+            shutdown: self.shutdown.clone(),
+            subscribers: Arc::clone(&self.subscribers),
+            delivery: Arc::clone(&self.delivery),
+        }
+    }
+
+    pub fn dropped_datasets(&self) -> u64 {
+        self.delivery.dropped.load(Ordering::Relaxed)
+    }
+
+    pub fn coalesced_datasets(&self) -> u64 {
+        self.delivery.coalesced.load(Ordering::Relaxed)
+    }
+
+    pub fn subscribe(
+        &self,
+        capacity: usize,
+        policy: BackpressurePolicy,
+    ) -> Result<Subscription<Output>, TaskError<Output>> {
+        self.subscribers.subscribe(capacity, policy)
+    }
+
+    pub fn watch(&self) -> Result<LatestDataSet<Output>, TaskError<Output>> {
+        self.subscribers.watch()
     }
 
     pub fn shutdown(&self) -> Result<(), TaskError<Output>> {
-        debug!("Initiating runner shutdown");
-        self.shutdown
-            .send(())
-            .map_err(|e| TaskError::ShutdownError(e.to_string()))?;
-        debug!("Runner shutdown signal sent");
-        Ok(())
+        send_shutdown(&self.shutdown, ShutdownMode::Graceful)
+    }
+
+    pub fn abort(&self) -> Result<(), TaskError<Output>> {
+        send_shutdown(&self.shutdown, ShutdownMode::Abort)
     }
 
-    #[instrument(skip(self), name = "run_task_runner", fields(tasks_count = %self.ctx.tasks.len()))]
+    #[instrument(
+        skip(self),
+        name = "run_task_runner",
+        fields(tasks_count = %self.ctx.tasks.len(), paused = false)
+    )]
     pub async fn run(&self) -> Result<(), TaskError<Output>> {
         info!("Starting task runner");
-        let mut shutdown = self.shutdown.subscribe();
-        let timeout = self.ctx.config.shutdown_timeout;
-        let result_sender = self.ctx.sender.clone();
-        let task_interval = self.ctx.interval.clone();
+        let mut commands = self.commands.lock().await;
+        let mut shutdown =
+            ShutdownSignal::new(self.shutdown.subscribe(), self.ctx.config.shutdown_timeout);
+        let mut state = self.start();
+        let (source_errors, failed_sources) = mpsc::unbounded_channel();
+        let feeds = Feeds(self.start_sources(source_errors));
+        let stop = self
+            .consolidate(&mut state, &mut commands, &mut shutdown, failed_sources)
+            .await;
+        drop(feeds);
+        match stop {
+            Ok(stop) => self.stop(state, stop, shutdown).await,
+            Err(e) => {
+                warn!("Runner failed, aborting worker tasks");
+                state.workers.abort();
+                Err(e)
+            }
+        }
+    }
 
-        debug!(interval_micros = %task_interval.as_micros(), "Configuring runner");
+    // Drives the ticks until shutdown, then drains the ticks still in flight
+    // unless the shutdown turns into an abort.
+    async fn consolidate(
+        &self,
+        state: &mut TickState<Input, Output>,
+        commands: &mut mpsc::UnboundedReceiver<Command<Input, Output>>,
+        shutdown: &mut ShutdownSignal,
+        mut failed_sources: mpsc::UnboundedReceiver<(String, TaskStatus)>,
+    ) -> Result<ShutdownMode, TaskError<Output>> {
+        let mut paused = false;
+        let mut in_flight: Vec<TickFuture<'_, Input, Output>> = Vec::new();
+        let mut queued: Vec<(usize, TimeWindow)> = Vec::new();
+        let mut paused_windows: HashMap<usize, TimeWindow> = HashMap::new();
+        let mut stop = loop {
+            tokio::select! {
+                mode = shutdown.recv() => {
+                    info!("Received shutdown signal, stopping consolidator");
+                    break mode;
+                }
+                Some((name, status)) = failed_sources.recv() => {
+                    state.source_failures.push((name, status));
+                }
+                Some(command) = commands.recv() => match command {
+                    Command::Pause => {
+                        info!("Pausing tick production");
+                        paused = true;
+                        Span::current().record("paused", true);
+                    }
+                    Command::Resume => {
+                        info!("Resuming tick production");
+                        paused = false;
+                        Span::current().record("paused", false);
+                    }
+                    Command::RunNow(window) => {
+                        for cadence in 0..state.workers.lanes.len() {
+                            if state.workers.is_idle(cadence) {
+                                continue;
+                            }
+                            let window = window
+                                .unwrap_or_else(|| state.workers.lanes[cadence].ticker.current_window());
+                            info!(cadence = %cadence, window = %window, "Queueing out-of-band tick");
+                            queued.push((cadence, window));
+                        }
+                    }
+                    Command::Change(change) => state.pending.push(change),
+                },
+                (cadence, window, skipped) = state.workers.next_tick() => {
+                    if state.workers.is_idle(cadence) {
+                        continue;
+                    }
+                    // Windows passed over while paused are reported on the
+                    // cadence's first DataSet after resuming.
+                    if paused {
+                        debug!(window = %window, "Runner paused, skipping tick");
+                        let missed = skipped.map_or(window, |skipped| cover(skipped, window));
+                        paused_windows
+                            .entry(cadence)
+                            .and_modify(|paused| *paused = cover(*paused, missed))
+                            .or_insert(missed);
+                        continue;
+                    }
+                    let skipped = match (paused_windows.remove(&cadence), skipped) {
+                        (Some(paused), Some(skipped)) => Some(cover(paused, skipped)),
+                        (paused, skipped) => paused.or(skipped),
+                    };
+                    in_flight.push(self.start_tick(state, shutdown, cadence, window, skipped));
+                }
+                (tick, result) = next_finished(&mut in_flight), if !in_flight.is_empty() => {
+                    if let Some(mode) = self.finish_tick(state, shutdown, tick, result).await? {
+                        break mode;
+                    }
+                }
+            }
 
-        let mut interval = interval(Duration::from_micros(task_interval.as_micros()));
-        let task_count = self.ctx.tasks.len();
+            // Out-of-band ticks start as soon as their cadence is free.
+            queued.retain(|&(cadence, window)| {
+                if state.workers.is_busy(cadence) {
+                    return true;
+                }
+                in_flight.push(self.start_tick(state, shutdown, cadence, window, None));
+                false
+            });
+        };
 
-        let (time_broadcaster, _) =
-            broadcast::channel::<(u64, u64)>(self.ctx.config.broadcast_channel_capacity);
-        let (output_sender, mut output_receiver) = mpsc::channel(task_count);
+        while let (ShutdownMode::Graceful, false) = (stop, in_flight.is_empty()) {
+            tokio::select! {
+                (tick, result) = next_finished(&mut in_flight) => {
+                    if let Some(mode) = self.finish_tick(state, shutdown, tick, result).await? {
+                        stop = mode;
+                    }
+                }
+                mode = shutdown.recv() => stop = mode,
+            }
+        }
+        Ok(stop)
+    }
 
-        debug!("Spawning {} worker tasks", task_count);
-        let mut worker_handles = Vec::new();
-        for task in self.ctx.tasks.iter() {
-            let task = Arc::clone(task);
-            let task_ctx = TaskContext {
-                data: Arc::clone(&self.ctx.data),
-                receiver: time_broadcaster.subscribe(),
-                sender: output_sender.clone(),
+    // Replays every full window between `start` and `end` back to back,
+    // without waiting on the schedule. Cadences are interleaved in the order
+    // their windows end.
+    #[instrument(
+        skip(self),
+        name = "run_task_backfill",
+        fields(tasks_count = %self.ctx.tasks.len())
+    )]
+    pub async fn backfill(
+        &self,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<(), TaskError<Output>> {
+        info!(start = %start, end = %end, "Starting backfill");
+        let _commands = self.commands.lock().await;
+        let mut shutdown =
+            ShutdownSignal::new(self.shutdown.subscribe(), self.ctx.config.shutdown_timeout);
+        let mut state = self.start();
+
+        let mut boundaries: Vec<Option<DateTime<Utc>>> = state
+            .workers
+            .lanes
+            .iter()
+            .enumerate()
+            .map(|(cadence, lane)| {
+                if state.workers.is_idle(cadence) {
+                    None
+                } else {
+                    lane.ticker.schedule().next_after(start)
+                }
+            })
+            .collect();
+
+        let mut stop = ShutdownMode::Graceful;
+        while let Some((cadence, boundary)) = boundaries
+            .iter()
+            .enumerate()
+            .filter_map(|(cadence, boundary)| boundary.map(|boundary| (cadence, boundary)))
+            .filter(|(_, boundary)| *boundary <= end)
+            .min_by_key(|(cadence, boundary)| (*boundary, *cadence))
+        {
+            let schedule = state.workers.lanes[cadence].ticker.schedule();
+            let window = schedule.window_ending(boundary);
+            boundaries[cadence] = schedule.next_after(boundary);
+
+            let processed = self
+                .process_tick(&mut state, &mut shutdown, cadence, window, None)
+                .await;
+            let processed = match processed {
+                Ok(processed) => processed,
+                Err(e) => {
+                    warn!("Backfill failed, aborting worker tasks");
+                    state.workers.abort();
+                    return Err(e);
+                }
             };
-            let mut worker = Worker::new(task, task_ctx);
-            let shutdown_rx = self.shutdown.subscribe();
-            let handle = tokio::spawn(async move { worker.run(shutdown_rx, timeout).await });
-            worker_handles.push(handle);
-        }
-
-        let consolidator = async move {
-            debug!("Starting result consolidator");
-            loop {
-                tokio::select! {
-                    _ = shutdown.recv() => {
-                        info!("Received shutdown signal, stopping consolidator");
-                        break;
-                    }
-                    _ = interval.tick() => {
-                        let end = get_current_time(&task_interval);
-                        let start = end - task_interval.as_u64();
-                        let mut dataset = DataSet::new(end);
-
-                        if let Err(e) = time_broadcaster.send((start, end)) {
-                            warn!(error = %e, "Failed to broadcast time window");
-                            return Err(TaskError::BroadcastError(e.to_string()));
-                        }
+            if let Some(mode) = processed {
+                stop = mode;
+                break;
+            }
+            if let Some(mode) = shutdown.try_recv() {
+                info!("Received shutdown signal, stopping backfill");
+                stop = mode;
+                break;
+            }
+        }
+        info!(ticks = %state.tick_id, "Backfill complete");
 
-                        collect_results(&mut output_receiver, &mut dataset, task_count).await?;
+        self.stop(state, stop, shutdown).await
+    }
 
-                        if let Err(e) = result_sender.send(dataset).await {
-                            warn!(error = %e, "Failed to send dataset");
-                            return Err(TaskError::DataSetSendError(e));
+    // Push sources feed the input in the background for as long as the
+    // runner runs; a source that fails is reported in the next DataSet.
+    fn start_sources(
+        &self,
+        failures: mpsc::UnboundedSender<(String, TaskStatus)>,
+    ) -> Vec<JoinHandle<()>> {
+        self.ctx
+            .sources
+            .iter()
+            .map(|source| {
+                let source = Arc::clone(source);
+                let data = Arc::clone(&self.ctx.data);
+                let failures = failures.clone();
+                tokio::spawn(async move {
+                    let name = source.name();
+                    match source.push(data).await {
+                        Ok(()) => debug!(source = %name, "Data source finished pushing"),
+                        Err(e) => {
+                            error!(source = %name, error = %e, "Data source failed");
+                            let _ = failures.send((name, TaskStatus::Failed(e.to_string())));
                         }
                     }
-                }
+                })
+            })
+            .collect()
+    }
+
+    async fn pull_sources(&self, dataset: &mut DataSet<Output>, window: TimeWindow) {
+        if self.ctx.sources.is_empty() {
+            return;
+        }
+        let Ok(mut data) =
+            time::timeout(self.ctx.config.shutdown_timeout, self.ctx.data.write()).await
+        else {
+            error!("Data write timeout, skipping data source pull");
+            for source in &self.ctx.sources {
+                dataset.set_status(&source.name(), TaskStatus::LockTimeout);
             }
-            Ok(())
+            return;
         };
+        for source in &self.ctx.sources {
+            if let Err(e) = source.pull(&mut data, window).await {
+                let name = source.name();
+                error!(source = %name, error = %e, "Data source pull failed");
+                dataset.set_status(&name, TaskStatus::Failed(e.to_string()));
+            }
+        }
+    }
+
+    fn start(&self) -> TickState<Input, Output> {
+        let schedule = &self.ctx.schedule;
+        debug!(schedule = %schedule, "Configuring runner");
 
-        let consolidator_handle = tokio::spawn(consolidator);
+        let task_count = self.ctx.tasks.len();
+        debug!("Spawning {} worker tasks", task_count);
+        let mut workers = WorkerSet {
+            data: Arc::clone(&self.ctx.data),
+            lock_timeout: self.ctx.config.shutdown_timeout,
+            slots: Vec::new(),
+            lanes: Vec::new(),
+            broadcast_capacity: self.ctx.config.broadcast_channel_capacity,
+            results_capacity: task_count.max(1),
+            clock: Arc::clone(&self.ctx.clock),
+            missed_ticks: self.ctx.config.missed_ticks,
+            timing: self.ctx.timing,
+        };
+        workers.add_cadence(schedule.clone());
+        for spec in self.ctx.tasks.iter() {
+            workers.spawn(spec.clone());
+        }
 
-        debug!("Waiting for worker tasks to complete");
-        for handle in worker_handles {
-            handle.await??;
+        debug!("Starting result consolidator");
+        TickState {
+            workers,
+            pending: Vec::new(),
+            last_good: HashMap::new(),
+            source_failures: Vec::new(),
+            tick_id: 0,
+        }
+    }
+
+    async fn stop(
+        &self,
+        state: TickState<Input, Output>,
+        mode: ShutdownMode,
+        mut shutdown: ShutdownSignal,
+    ) -> Result<(), TaskError<Output>> {
+        shutdown.update(mode);
+        match mode {
+            ShutdownMode::Graceful => {
+                debug!("Consolidator stopped, waiting for worker tasks");
+                state.workers.join(&mut shutdown).await?;
+            }
+            ShutdownMode::Abort => {
+                warn!("Aborting worker tasks");
+                state.workers.abort();
+            }
         }
-        debug!("Worker tasks completed, waiting for consolidator");
-        consolidator_handle.await??;
         info!("Runner shutdown complete");
 
         Ok(())
     }
+
+    async fn snapshot(&self) -> Result<Option<Arc<Input>>, Elapsed> {
+        let Some(snapshot) = &self.ctx.snapshot else {
+            return Ok(None);
+        };
+        let data = time::timeout(self.ctx.config.shutdown_timeout, self.ctx.data.read()).await?;
+        debug!("Taking input snapshot");
+        Ok(Some(Arc::new(snapshot(&data))))
+    }
+
+    // Runs the tick one dependency layer at a time, so every task sees the
+    // outputs of its upstream tasks for the same window. Tasks whose upstream
+    // did not succeed are skipped.
+    async fn run_layers(
+        &self,
+        tick: &mut InFlightTick<Input, Output>,
+        input: Option<Arc<Input>>,
+    ) -> Result<Option<ShutdownMode>, TaskError<Output>> {
+        let dependencies = &self.ctx.dependencies;
+        let dataset = &mut tick.dataset;
+        let names: Vec<&str> = tick
+            .expected
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect();
+        // Cycles are rejected when the context is built.
+        let layers = graph::layers(&names, dependencies).unwrap_or_else(|_| vec![names.clone()]);
+        let mut upstream = Arc::new(DataSet::new(dataset.window));
+
+        for (layer, tasks) in layers.iter().enumerate() {
+            let mut runnable = Vec::new();
+            for (name, deadline) in tick
+                .expected
+                .iter()
+                .filter(|(name, _)| tasks.contains(&name.as_str()))
+            {
+                let failed = graph::upstream(dependencies, name)
+                    .filter(|up| names.contains(up))
+                    .find(|up| !dataset.status(up).is_some_and(TaskStatus::is_ok));
+                match failed {
+                    Some(failed) => {
+                        warn!(task_name = %name, upstream = %failed, "Upstream task failed, skipping task");
+                        let reason = format!("upstream task {} failed", failed);
+                        dataset.set_status(name, TaskStatus::Skipped(reason));
+                    }
+                    None => runnable.push((name.clone(), *deadline)),
+                }
+            }
+            if runnable.is_empty() {
+                continue;
+            }
+
+            if layer > 0 {
+                if let Some(clone) = &self.ctx.output_clone {
+                    upstream = Arc::new(dataset.clone_with(&**clone));
+                }
+            }
+
+            let broadcast = Tick {
+                id: tick.id,
+                cadence: tick.cadence,
+                window: dataset.window,
+                input: input.clone(),
+                tasks: Arc::new(runnable.iter().map(|(name, _)| name.clone()).collect()),
+                upstream: Arc::clone(&upstream),
+            };
+            if let Err(e) = tick.broadcaster.send(broadcast) {
+                warn!(error = %e, "Failed to broadcast time window");
+                return Err(TaskError::BroadcastError(e.to_string()));
+            }
+
+            let stop = collect_results(
+                &mut tick.results,
+                dataset,
+                tick.id,
+                &runnable,
+                &tick.tasks,
+                &mut tick.shutdown,
+            )
+            .await?;
+            if stop.is_some() {
+                for name in layers[layer + 1..].iter().flatten() {
+                    dataset.set_status(name, TaskStatus::Cancelled);
+                }
+                return Ok(stop);
+            }
+        }
+        Ok(None)
+    }
+
+    // Hands the dataset to the subscribers and the result receiver, waiting
+    // for room on those that block for as long as the runner is not
+    // stopping. Once a shutdown arrives the wait is bounded by its
+    // deadline, and an abort drops the dataset straight away.
+    async fn deliver(
+        &self,
+        dataset: DataSet<Output>,
+        shutdown: &mut ShutdownSignal,
+    ) -> Result<(), TaskError<Output>> {
+        let policy = self.ctx.config.backpressure;
+        let window = dataset.window;
+        let send = async {
+            let receiver_closed = self.ctx.sender.is_closed();
+            match self.subscribers.publish(dataset, receiver_closed).await {
+                Some(dataset) => self.ctx.sender.send(dataset).await,
+                None => Ok(Delivery::Queued),
+            }
+        };
+        tokio::pin!(send);
+        let delivery = loop {
+            let deadline = shutdown.deadline();
+            tokio::select! {
+                biased;
+                delivery = &mut send => break delivery,
+                _ = shutdown.recv() => {}
+                _ = time::sleep_until(deadline), if shutdown.mode().is_some() => {
+                    let dropped = self.delivery.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+                    warn!(
+                        window = %window,
+                        dropped = %dropped,
+                        "Receivers are full while shutting down, dropping dataset"
+                    );
+                    return Ok(());
+                }
+            }
+        };
+        match delivery {
+            Ok(Delivery::Queued) => {}
+            Ok(Delivery::Dropped(dataset)) => {
+                let dropped = self.delivery.dropped.fetch_add(1, Ordering::Relaxed) + 1;
+                warn!(
+                    window = %dataset.window,
+                    policy = ?policy,
+                    dropped = %dropped,
+                    "Result receiver is full, dropping dataset"
+                );
+            }
+            Ok(Delivery::Coalesced(window)) => {
+                let coalesced = self.delivery.coalesced.fetch_add(1, Ordering::Relaxed) + 1;
+                warn!(
+                    window = %window,
+                    coalesced = %coalesced,
+                    "Result receiver is full, coalescing dataset"
+                );
+            }
+            Err(e) => {
+                warn!("Failed to send dataset, receiver closed");
+                return Err(e.into());
+            }
+        }
+        Ok(())
+    }
+
+    // Runs a single tick to completion, following shutdown signals while it
+    // is in flight.
+    async fn process_tick(
+        &self,
+        state: &mut TickState<Input, Output>,
+        shutdown: &mut ShutdownSignal,
+        cadence: usize,
+        window: TimeWindow,
+        skipped: Option<TimeWindow>,
+    ) -> Result<Option<ShutdownMode>, TaskError<Output>> {
+        let mut running = self.start_tick(state, shutdown, cadence, window, skipped);
+        let (tick, result) = loop {
+            tokio::select! {
+                outcome = &mut running => break outcome,
+                _ = shutdown.recv() => {}
+            }
+        };
+        self.finish_tick(state, shutdown, tick, result).await
+    }
+
+    // Takes the cadence's lane and returns the tick running on it. The tick
+    // only borrows the runner, so ticks on other cadences can run alongside.
+    fn start_tick(
+        &self,
+        state: &mut TickState<Input, Output>,
+        shutdown: &ShutdownSignal,
+        cadence: usize,
+        window: TimeWindow,
+        skipped: Option<TimeWindow>,
+    ) -> TickFuture<'_, Input, Output> {
+        self.apply_pending(state);
+
+        let lane = &mut state.workers.lanes[cadence];
+        let results = lane
+            .receiver
+            .take()
+            .expect("cadence already has a tick in flight");
+        let mut dataset = DataSet::new(window).with_schedule(lane.ticker.schedule().clone());
+        dataset.skipped = skipped;
+        for (name, status) in state.source_failures.drain(..) {
+            dataset.set_status(&name, status);
+        }
+        state.tick_id += 1;
+        let mut tick = InFlightTick {
+            id: state.tick_id,
+            cadence,
+            broadcaster: lane.broadcaster.clone(),
+            results,
+            shutdown: shutdown.watch(),
+            dataset,
+            expected: state.workers.expected(cadence),
+            tasks: state.workers.tasks(cadence),
+        };
+
+        let span = info_span!("run_task_tick", tick = %tick.id, cadence = %cadence);
+        Box::pin(
+            async move {
+                self.pull_sources(&mut tick.dataset, window).await;
+                let result = match self.snapshot().await {
+                    Ok(input) => self.run_layers(&mut tick, input).await,
+                    Err(_) => {
+                        error!("Data read timeout while taking input snapshot, skipping tick");
+                        for (name, _) in &tick.expected {
+                            tick.dataset.set_status(name, TaskStatus::LockTimeout);
+                        }
+                        Ok(None)
+                    }
+                };
+                (tick, result)
+            }
+            .instrument(span),
+        )
+    }
+
+    // Hands the lane back and delivers the tick's dataset.
+    async fn finish_tick(
+        &self,
+        state: &mut TickState<Input, Output>,
+        shutdown: &mut ShutdownSignal,
+        tick: InFlightTick<Input, Output>,
+        result: Result<Option<ShutdownMode>, TaskError<Output>>,
+    ) -> Result<Option<ShutdownMode>, TaskError<Output>> {
+        let InFlightTick {
+            cadence,
+            results,
+            mut dataset,
+            expected,
+            ..
+        } = tick;
+        state.workers.lanes[cadence].receiver = Some(results);
+        self.apply_pending(state);
+
+        let stop = result?;
+        if let Some(ShutdownMode::Abort) = stop {
+            return Ok(stop);
+        }
+
+        let failure =
+            state
+                .workers
+                .abort_on_failure(cadence)
+                .find_map(|name| match dataset.status(name) {
+                    Some(TaskStatus::Failed(e)) => Some(format!("Task {} failed: {}", name, e)),
+                    _ => None,
+                });
+
+        if resolve_missing_results(
+            &self.ctx.config.missing_results,
+            &mut dataset,
+            &expected,
+            &mut state.last_good,
+        ) {
+            self.deliver(dataset, shutdown).await?;
+        }
+
+        if let Some(failure) = failure {
+            error!(error = %failure, "Aborting runner after task failure");
+            return Err(TaskError::TaskError(failure));
+        }
+        // A signal may have arrived while delivery was waiting.
+        Ok(shutdown.mode().or(stop))
+    }
+
+    // Applies queued task changes in order, stopping at the first one that
+    // touches a cadence with a tick in flight.
+    fn apply_pending(&self, state: &mut TickState<Input, Output>) {
+        let ready = state
+            .pending
+            .iter()
+            .take_while(|change| !state.workers.is_blocked(change))
+            .count();
+        for change in state.pending.drain(..ready) {
+            state.workers.apply(change);
+        }
+    }
+}
+
+// Resolves with the first tick in flight to finish.
+async fn next_finished<Input, Output>(
+    in_flight: &mut Vec<TickFuture<'_, Input, Output>>,
+) -> TickOutcome<Input, Output> {
+    std::future::poll_fn(|cx| {
+        let finished = in_flight.iter_mut().enumerate().find_map(|(index, tick)| {
+            match tick.as_mut().poll(cx) {
+                Poll::Ready(outcome) => Some((index, outcome)),
+                Poll::Pending => None,
+            }
+        });
+        match finished {
+            Some((index, outcome)) => {
+                drop(in_flight.swap_remove(index));
+                Poll::Ready(outcome)
+            }
+            None => Poll::Pending,
+        }
+    })
+    .await
+}
+
+fn send_shutdown<Output>(
+    shutdown: &broadcast::Sender<ShutdownMode>,
+    mode: ShutdownMode,
+) -> Result<(), TaskError<Output>> {
+    debug!(mode = ?mode, "Initiating runner shutdown");
+    shutdown
+        .send(mode)
+        .map_err(|e| TaskError::ShutdownError(e.to_string()))?;
+    debug!("Runner shutdown signal sent");
+    Ok(())
 }
 
 #[instrument(
-    skip(output_receiver, dataset),
-    fields(task_count = %task_count),
+    skip(output_receiver, dataset, expected, tasks, shutdown),
+    fields(task_count = %expected.len(), tick = %tick),
     name = "collect_task_results"
 )]
-async fn collect_results<Output>(
+async fn collect_results<Input, Output>(
     output_receiver: &mut mpsc::Receiver<TaskResult<Output>>,
     dataset: &mut DataSet<Output>,
-    task_count: usize,
-) -> Result<(), TaskError<Output>> {
+    tick: u64,
+    expected: &[(String, Option<Duration>)],
+    tasks: &HashMap<String, Task<Input, Output>>,
+    shutdown: &mut watch::Receiver<Option<Stop>>,
+) -> Result<Option<ShutdownMode>, TaskError<Output>> {
     debug!("Starting result collection");
+    // Lets a task know its result for the window will not be used.
+    let discard = |name: &str, window: TimeWindow| {
+        if let Some(task) = tasks.get(name) {
+            task.discard(window);
+        }
+    };
+
+    let started = Instant::now();
+    let mut pending: HashMap<&str, Option<Instant>> = expected
+        .iter()
+        .map(|(name, deadline)| (name.as_str(), deadline.map(|d| started + d)))
+        .collect();
+    let mut stop = None;
+    let mut drain_deadline = None;
+    let mut watching = true;
 
-    for i in 0..task_count {
-        match output_receiver.recv().await {
-            Some(TaskResult { name, result }) => {
-                debug!(task_name = %name, remaining = %(task_count - i - 1), "Collected task result");
-                match result {
-                    Some(result) => dataset.insert(&name, result),
-                    None => error!("Task {} returned None", name), // TODO: Add config option to ignore None results
+    while !pending.is_empty() {
+        match *shutdown.borrow_and_update() {
+            Some(Stop {
+                mode: ShutdownMode::Abort,
+                ..
+            }) => {
+                warn!(unfinished = ?pending.keys().collect::<Vec<_>>(), "Abandoning in-flight tick");
+                for name in pending.keys() {
+                    discard(name, dataset.window);
                 }
+                return Ok(Some(ShutdownMode::Abort));
             }
-            None => {
-                warn!("Result channel closed unexpectedly");
-                return Err(TaskError::TaskError("Result channel closed unexpectedly".to_string()));
+            Some(Stop { deadline, .. }) if stop.is_none() => {
+                info!(remaining = %pending.len(), "Draining in-flight tick before shutdown");
+                stop = Some(ShutdownMode::Graceful);
+                drain_deadline = Some(deadline);
             }
+            _ => {}
+        }
+        let deadline = pending
+            .values()
+            .flatten()
+            .chain(drain_deadline.iter())
+            .min()
+            .copied();
+
+        tokio::select! {
+            changed = shutdown.changed(), if watching => watching = changed.is_ok(),
+            _ = time::sleep_until(deadline.unwrap_or_else(Instant::now)), if deadline.is_some() => {
+                let now = Instant::now();
+                pending.retain(|name, task_deadline| {
+                    if task_deadline.is_some_and(|d| d <= now) {
+                        warn!(task_name = %name, "Task missed its deadline");
+                        dataset.set_status(name, TaskStatus::TimedOut);
+                        discard(name, dataset.window);
+                        false
+                    } else if drain_deadline.is_some_and(|d| d <= now) {
+                        warn!(task_name = %name, "Task did not finish before shutdown");
+                        dataset.set_status(name, TaskStatus::Cancelled);
+                        discard(name, dataset.window);
+                        false
+                    } else {
+                        true
+                    }
+                });
+            }
+            received = output_receiver.recv() => match received {
+                Some(TaskResult {
+                    name,
+                    tick: result_tick,
+                    window,
+                    status,
+                    result,
+                }) => {
+                    if result_tick != tick || pending.remove(name.as_str()).is_none() {
+                        warn!(task_name = %name, tick = %result_tick, "Discarding late task result");
+                        if result.is_some() {
+                            discard(&name, window);
+                        }
+                        continue;
+                    }
+                    debug!(task_name = %name, remaining = %pending.len(), "Collected task result");
+                    match result {
+                        Some(result) => dataset.insert(&name, result),
+                        None => {
+                            warn!(task_name = %name, status = %status, "Task returned no result");
+                            dataset.set_status(&name, status);
+                        }
+                    }
+                }
+                None => {
+                    warn!("Result channel closed unexpectedly");
+                    return Err(TaskError::TaskError(
+                        "Result channel closed unexpectedly".to_string(),
+                    ));
+                }
+            },
         }
     }
     debug!("Result collection complete");
-    Ok(())
+    Ok(stop)
+}
+
+fn cover(a: TimeWindow, b: TimeWindow) -> TimeWindow {
+    TimeWindow::new(a.start.min(b.start), a.end.max(b.end))
 }
 
-fn get_current_time(task_interval: &TaskInterval) -> u64 {
-    match task_interval {
-        TaskInterval::Micros(_) => chrono::Utc::now().timestamp_micros() as u64,
-        TaskInterval::Millis(_) => chrono::Utc::now().timestamp_millis() as u64,
-        TaskInterval::Seconds(_) => chrono::Utc::now().timestamp() as u64,
-        TaskInterval::Minutes(_) => (chrono::Utc::now().timestamp() * 60) as u64,
+fn resolve_missing_results<Output>(
+    policy: &MissingResultPolicy<Output>,
+    dataset: &mut DataSet<Output>,
+    expected: &[(String, Option<Duration>)],
+    last_good: &mut HashMap<String, Output>,
+) -> bool {
+    let missing: Vec<&str> = expected
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .filter(|name| dataset.get(name).is_none())
+        .collect();
+
+    match policy {
+        MissingResultPolicy::Ignore => {}
+        MissingResultPolicy::Fallback(fallback) => {
+            for name in missing {
+                debug!(task_name = %name, "Inserting fallback result");
+                dataset.data.insert(name.to_string(), fallback(name));
+            }
+        }
+        MissingResultPolicy::CarryForward(carry) => {
+            for (name, value) in dataset.iter() {
+                if dataset.status(name).is_some_and(TaskStatus::is_ok) {
+                    last_good.insert(name.clone(), carry(value));
+                }
+            }
+            for name in missing {
+                if let Some(value) = last_good.get(name) {
+                    debug!(task_name = %name, "Carrying forward last good result");
+                    dataset.data.insert(name.to_string(), carry(value));
+                }
+            }
+        }
+        MissingResultPolicy::DropDataSet => {
+            if !missing.is_empty() {
+                warn!(missing = ?missing, "Dropping dataset with missing results");
+                return false;
+            }
+        }
     }
+    true
 }
@@ -0,0 +1,238 @@
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use tracing::warn;
+
+use crate::clock::Clock;
+use crate::config::MissedTickPolicy;
+use crate::cron::CronSchedule;
+use crate::interval::TaskInterval;
+use crate::window::TimeWindow;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Schedule {
+    Interval(TaskInterval),
+    Aligned(TaskInterval),
+    Cron(CronSchedule),
+}
+
+impl Schedule {
+    // A zero interval never fires, since the next firing would not move on.
+    pub fn next_after(&self, time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            Schedule::Interval(interval) | Schedule::Aligned(interval) if interval.is_zero() => {
+                None
+            }
+            Schedule::Interval(interval) => time.checked_add_signed(length(interval)),
+            Schedule::Aligned(interval) => {
+                let period = period(interval)?;
+                let next = (time.timestamp_micros().div_euclid(period) + 1).checked_mul(period)?;
+                DateTime::from_timestamp_micros(next)
+            }
+            Schedule::Cron(cron) => cron.next_after(time),
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        match self {
+            Schedule::Interval(interval) | Schedule::Aligned(interval) => interval.is_zero(),
+            Schedule::Cron(_) => false,
+        }
+    }
+
+    pub fn last_at_or_before(&self, time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            Schedule::Interval(_) => Some(time),
+            Schedule::Aligned(interval) => {
+                let period = period(interval)?.max(1);
+                let last = time.timestamp_micros().div_euclid(period) * period;
+                DateTime::from_timestamp_micros(last)
+            }
+            Schedule::Cron(cron) => cron.prev_before(time + chrono::Duration::nanoseconds(1)),
+        }
+    }
+
+    pub fn window_ending(&self, end: DateTime<Utc>) -> TimeWindow {
+        match self {
+            Schedule::Interval(interval) | Schedule::Aligned(interval) => {
+                TimeWindow::ending_at(end, length(interval))
+            }
+            Schedule::Cron(cron) => TimeWindow::new(cron.prev_before(end).unwrap_or(end), end),
+        }
+    }
+
+    pub fn window_at(&self, time: DateTime<Utc>) -> TimeWindow {
+        self.window_ending(self.last_at_or_before(time).unwrap_or(time))
+    }
+
+    // Reads the time from `clock`, like the runner does, so it agrees with
+    // the runner under a test clock.
+    pub fn current_window(&self, clock: &dyn Clock) -> TimeWindow {
+        self.window_at(clock.now())
+    }
+}
+
+impl From<TaskInterval> for Schedule {
+    fn from(interval: TaskInterval) -> Self {
+        Schedule::Interval(interval)
+    }
+}
+
+impl From<CronSchedule> for Schedule {
+    fn from(cron: CronSchedule) -> Self {
+        Schedule::Cron(cron)
+    }
+}
+
+impl fmt::Display for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Schedule::Interval(interval) => write!(f, "every {}", interval),
+            Schedule::Aligned(interval) => write!(f, "aligned to {}", interval),
+            Schedule::Cron(cron) => write!(f, "cron '{}'", cron),
+        }
+    }
+}
+
+// Shifts when ticks fire without moving the window boundaries handed to
+// tasks.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TickTiming {
+    pub start_delay: Duration,
+    pub phase: Duration,
+    pub jitter: Duration,
+}
+
+pub(crate) struct Ticker {
+    schedule: Schedule,
+    clock: Arc<dyn Clock>,
+    missed: MissedTickPolicy,
+    timing: TickTiming,
+    next: Option<DateTime<Utc>>,
+    fire_at: Option<DateTime<Utc>>,
+    rng: u64,
+}
+
+impl Ticker {
+    pub(crate) fn new(
+        schedule: Schedule,
+        clock: Arc<dyn Clock>,
+        missed: MissedTickPolicy,
+        timing: TickTiming,
+    ) -> Self {
+        Ticker {
+            schedule,
+            clock,
+            missed,
+            timing,
+            next: None,
+            fire_at: None,
+            rng: RandomState::new().build_hasher().finish() | 1,
+        }
+    }
+
+    pub(crate) fn schedule(&self) -> &Schedule {
+        &self.schedule
+    }
+
+    pub(crate) fn current_window(&self) -> TimeWindow {
+        self.schedule.current_window(&*self.clock)
+    }
+
+    // Each firing is derived from the previous one rather than from the
+    // clock, so windows stay exact and contiguous. The next firing is stored
+    // before sleeping, which makes this safe to cancel.
+    //
+    // A tick counts as missed once the firing after it is also due. Burst
+    // then replays every missed window in turn, Delay folds them into one
+    // longer window and restarts the schedule from there, and Skip jumps to
+    // the latest window and returns the gap it skipped over.
+    pub(crate) async fn tick(&mut self) -> (TimeWindow, Option<TimeWindow>) {
+        let phase = chrono_duration(self.timing.phase);
+        let next = match self.next {
+            Some(next) => next,
+            None => {
+                let now = self.clock.now() + chrono_duration(self.timing.start_delay);
+                let first = match &self.schedule {
+                    Schedule::Interval(interval) if !interval.is_zero() => Some(now),
+                    schedule => schedule.next_after(now),
+                };
+                match first {
+                    Some(first) => *self.next.insert(first),
+                    None => return std::future::pending().await,
+                }
+            }
+        };
+        let fire_at = match self.fire_at {
+            Some(fire_at) => fire_at,
+            None => {
+                let jitter = self.jitter();
+                *self.fire_at.insert(next + phase + jitter)
+            }
+        };
+        self.clock.sleep_until(fire_at).await;
+        self.fire_at = None;
+
+        let schedule = &self.schedule;
+        let window = schedule.window_ending(next);
+        let now = self.clock.now() - phase;
+        let mut latest = next;
+        if self.missed != MissedTickPolicy::Burst {
+            while let Some(due) = schedule.next_after(latest).filter(|due| *due <= now) {
+                latest = due;
+            }
+        }
+        if latest == next {
+            self.next = schedule.next_after(next);
+            return (window, None);
+        }
+
+        match self.missed {
+            MissedTickPolicy::Delay => {
+                let end = match schedule {
+                    Schedule::Interval(_) => now,
+                    _ => latest,
+                };
+                warn!(schedule = %schedule, missed_until = %end, "Tick overran, delaying schedule");
+                self.next = schedule.next_after(end);
+                (TimeWindow::new(window.start, end), None)
+            }
+            _ => {
+                let latest_window = schedule.window_ending(latest);
+                let skipped = TimeWindow::new(window.start, latest_window.start);
+                warn!(schedule = %schedule, skipped = %skipped, "Tick overran, skipping missed windows");
+                self.next = schedule.next_after(latest);
+                (latest_window, Some(skipped))
+            }
+        }
+    }
+
+    // xorshift64 is plenty to spread runners apart.
+    fn jitter(&mut self) -> chrono::Duration {
+        let max = self.timing.jitter.as_micros() as u64;
+        if max == 0 {
+            return chrono::Duration::zero();
+        }
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        chrono::Duration::microseconds((self.rng % (max + 1)) as i64)
+    }
+}
+
+// Intervals longer than chrono can represent never come round again.
+fn period(interval: &TaskInterval) -> Option<i64> {
+    i64::try_from(interval.as_micros()).ok()
+}
+
+fn length(interval: &TaskInterval) -> chrono::Duration {
+    chrono::Duration::microseconds(period(interval).unwrap_or(i64::MAX))
+}
+
+fn chrono_duration(duration: Duration) -> chrono::Duration {
+    chrono::Duration::from_std(duration).unwrap_or(chrono::Duration::MAX)
+}
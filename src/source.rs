@@ -0,0 +1,69 @@
+use std::sync::Arc;
+
+use tokio::sync::{mpsc, Mutex, RwLock};
+
+use crate::error::SourceError;
+use crate::task::BoxFuture;
+use crate::window::TimeWindow;
+
+// A source can pull, push, or both. `pull` runs before every tick with the
+// input's write lock held; `push` is spawned once when the runner starts and
+// writes to the input whenever it has new data.
+pub trait DataSource<Input>: Send + Sync {
+    fn name(&self) -> String;
+
+    fn pull<'a>(
+        &'a self,
+        _data: &'a mut Input,
+        _window: TimeWindow,
+    ) -> BoxFuture<'a, Result<(), SourceError>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    fn push(&self, _data: Arc<RwLock<Input>>) -> BoxFuture<'_, Result<(), SourceError>> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+type Apply<Input, Item> = Box<dyn Fn(&mut Input, Item) + Send + Sync>;
+
+pub struct ChannelSource<Input, Item> {
+    name: String,
+    receiver: Mutex<mpsc::Receiver<Item>>,
+    apply: Apply<Input, Item>,
+}
+
+impl<Input, Item> ChannelSource<Input, Item> {
+    pub fn new(
+        name: &str,
+        receiver: mpsc::Receiver<Item>,
+        apply: impl Fn(&mut Input, Item) + Send + Sync + 'static,
+    ) -> Self {
+        ChannelSource {
+            name: name.to_string(),
+            receiver: Mutex::new(receiver),
+            apply: Box::new(apply),
+        }
+    }
+}
+
+impl<Input: Send + Sync, Item: Send> DataSource<Input> for ChannelSource<Input, Item> {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn push(&self, data: Arc<RwLock<Input>>) -> BoxFuture<'_, Result<(), SourceError>> {
+        Box::pin(async move {
+            let mut receiver = self.receiver.lock().await;
+            while let Some(item) = receiver.recv().await {
+                let mut data = data.write().await;
+                (self.apply)(&mut data, item);
+                // Apply whatever else is queued under the same lock.
+                while let Ok(item) = receiver.try_recv() {
+                    (self.apply)(&mut data, item);
+                }
+            }
+            Ok(())
+        })
+    }
+}
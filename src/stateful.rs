@@ -0,0 +1,136 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, TryLockError};
+
+use crate::context::OutputClone;
+use crate::task::Runnable;
+use crate::window::TimeWindow;
+use crate::TaskError;
+
+// A task that keeps rolling state between ticks in its own fields. `history`
+// holds the task's previous successful outputs, oldest first, when kept with
+// `Stateful::with_history`.
+pub trait StatefulRunnable<Input, Output>: Send {
+    fn name(&self) -> String;
+    fn run(
+        &mut self,
+        data: &Input,
+        history: &[Output],
+        window: TimeWindow,
+    ) -> Result<Output, TaskError<Output>>;
+}
+
+struct State<R, Output> {
+    task: R,
+    history: VecDeque<Output>,
+    // The latest output joins the history at the next run, unless the
+    // runner discarded it in the meantime.
+    pending: Option<(TimeWindow, Output)>,
+}
+
+// Runs a `StatefulRunnable` as a regular task. A Blocking or Pool run that
+// overruns its timeout keeps going in the background, so the next tick can
+// find it still holding the state; that tick fails instead of waiting.
+pub struct Stateful<R, Output> {
+    name: String,
+    depth: usize,
+    clone: Option<OutputClone<Output>>,
+    state: Mutex<State<R, Output>>,
+    // Kept apart from `state` so a discard never waits on a running task.
+    discarded: Mutex<Option<TimeWindow>>,
+}
+
+impl<R, Output> Stateful<R, Output> {
+    pub fn new<Input>(task: R) -> Self
+    where
+        R: StatefulRunnable<Input, Output>,
+    {
+        Stateful {
+            name: task.name(),
+            depth: 0,
+            clone: None,
+            state: Mutex::new(State {
+                task,
+                history: VecDeque::new(),
+                pending: None,
+            }),
+            discarded: Mutex::new(None),
+        }
+    }
+
+    pub fn with_history(
+        mut self,
+        depth: usize,
+        clone: impl Fn(&Output) -> Output + Send + Sync + 'static,
+    ) -> Self {
+        self.depth = depth;
+        self.clone = Some(Arc::new(clone));
+        self
+    }
+
+    pub fn with_cloned_history(self, depth: usize) -> Self
+    where
+        Output: Clone + 'static,
+    {
+        self.with_history(depth, Output::clone)
+    }
+
+    fn commit(&self, state: &mut State<R, Output>) {
+        let Some((window, output)) = state.pending.take() else {
+            return;
+        };
+        let discarded = self
+            .discarded
+            .lock()
+            .map(|mut discarded| discarded.take())
+            .unwrap_or_default();
+        if discarded == Some(window) {
+            return;
+        }
+        state.history.push_back(output);
+        while state.history.len() > self.depth {
+            state.history.pop_front();
+        }
+    }
+}
+
+impl<Input, Output, R> Runnable<Input, Output> for Stateful<R, Output>
+where
+    R: StatefulRunnable<Input, Output>,
+    Output: Send,
+{
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn run(&self, data: &Input, window: TimeWindow) -> Result<Output, TaskError<Output>> {
+        let mut state = match self.state.try_lock() {
+            Ok(state) => state,
+            Err(TryLockError::WouldBlock) => {
+                return Err(TaskError::TaskError(format!(
+                    "Previous run of task {} is still in progress",
+                    self.name
+                )));
+            }
+            Err(TryLockError::Poisoned(_)) => {
+                return Err(TaskError::TaskError(format!(
+                    "State of task {} is poisoned",
+                    self.name
+                )));
+            }
+        };
+        self.commit(&mut state);
+        let State { task, history, .. } = &mut *state;
+
+        let output = task.run(data, history.make_contiguous(), window)?;
+        if let Some(clone) = &self.clone {
+            state.pending = Some((window, clone(&output)));
+        }
+        Ok(output)
+    }
+
+    fn discard(&self, window: TimeWindow) {
+        if let Ok(mut discarded) = self.discarded.lock() {
+            *discarded = Some(window);
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::watch;
+use tracing::{debug, warn};
+
+use crate::config::BackpressurePolicy;
+use crate::context::OutputClone;
+use crate::data_types::DataSet;
+use crate::error::TaskError;
+use crate::queue::{data_channel, DataReceiver, DataSender, Delivery};
+
+pub type Subscription<Output> = DataReceiver<Output>;
+pub type LatestDataSet<Output> = watch::Receiver<Option<Arc<DataSet<Output>>>>;
+
+// Fans every DataSet out to any number of subscribers on top of the
+// context's receiver. Each subscription has its own queue and backpressure
+// policy, so a slow subscriber only holds up the runner if it asks to.
+pub(crate) struct Subscribers<Output> {
+    clone: Option<OutputClone<Output>>,
+    senders: Mutex<Vec<DataSender<Output>>>,
+    latest: watch::Sender<Option<Arc<DataSet<Output>>>>,
+}
+
+impl<Output> Subscribers<Output> {
+    pub(crate) fn new(clone: Option<OutputClone<Output>>) -> Self {
+        Subscribers {
+            clone,
+            senders: Mutex::new(Vec::new()),
+            latest: watch::Sender::new(None),
+        }
+    }
+
+    pub(crate) fn subscribe(
+        &self,
+        capacity: usize,
+        policy: BackpressurePolicy,
+    ) -> Result<Subscription<Output>, TaskError<Output>> {
+        self.check_clone()?;
+        let (sender, receiver) = data_channel(capacity, policy);
+        self.senders
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(sender);
+        Ok(receiver)
+    }
+
+    pub(crate) fn watch(&self) -> Result<LatestDataSet<Output>, TaskError<Output>> {
+        self.check_clone()?;
+        Ok(self.latest.subscribe())
+    }
+
+    fn check_clone(&self) -> Result<(), TaskError<Output>> {
+        match self.clone {
+            Some(_) => Ok(()),
+            None => Err(TaskError::TaskError(
+                "Subscribers need an output clone, see `with_cloned_outputs`".to_string(),
+            )),
+        }
+    }
+
+    // Hands a copy of the DataSet to every subscriber, and returns it if the
+    // context's receiver still needs it. A subscriber that blocks holds up
+    // the rest until it has room.
+    pub(crate) async fn publish(
+        &self,
+        dataset: DataSet<Output>,
+        receiver_closed: bool,
+    ) -> Option<DataSet<Output>> {
+        let senders = {
+            let mut senders = self.senders.lock().unwrap_or_else(|e| e.into_inner());
+            senders.retain(|sender| !sender.is_closed());
+            senders.clone()
+        };
+        let watching = self.latest.receiver_count() > 0;
+        let Some(clone) = self
+            .clone
+            .as_ref()
+            .filter(|_| !senders.is_empty() || watching)
+        else {
+            return Some(dataset);
+        };
+
+        debug!(subscribers = %senders.len(), "Publishing dataset to subscribers");
+        for sender in &senders {
+            // Only fails once the subscriber is gone, it is pruned next time.
+            match sender.send(dataset.clone_with(&**clone)).await {
+                Ok(Delivery::Dropped(dropped)) => {
+                    warn!(window = %dropped.window, "Subscriber is full, dropping dataset");
+                }
+                Ok(Delivery::Coalesced(window)) => {
+                    warn!(window = %window, "Subscriber is full, coalescing dataset");
+                }
+                Ok(Delivery::Queued) | Err(_) => {}
+            }
+        }
+
+        if receiver_closed {
+            if watching {
+                self.latest.send_replace(Some(Arc::new(dataset)));
+            }
+            return None;
+        }
+        if watching {
+            self.latest
+                .send_replace(Some(Arc::new(dataset.clone_with(&**clone))));
+        }
+        Some(dataset)
+    }
+}
@@ -1,98 +1,511 @@
+use std::future::Future;
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
 use std::sync::Arc;
-use tokio::sync::{broadcast, mpsc, RwLock};
-use tokio::time::{self, Duration};
-use tracing::{debug, error, info, instrument};
+use std::task::{Context, Poll};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{broadcast, mpsc, OwnedRwLockReadGuard, RwLock};
+use tokio::time::{self, Duration, Instant};
+use tracing::{debug, error, info, instrument, warn};
 
+use crate::config::{ExecutionMode, FailurePolicy, TaskConfig};
+use crate::data_types::{AnyOutput, DataSet, TaskStatus};
+use crate::window::TimeWindow;
 use crate::TaskError;
 
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
 pub trait Runnable<Input, Output>: Send + Sync {
     fn name(&self) -> String;
-    fn run(&self, data: &Input, start: u64, end: u64) -> Result<Output, TaskError<Output>>;
+    fn run(&self, data: &Input, window: TimeWindow) -> Result<Output, TaskError<Output>>;
+
+    // Called instead of `run` so tasks with dependencies can read the
+    // outputs of their upstream tasks from the same window.
+    fn run_with_upstream(
+        &self,
+        data: &Input,
+        _upstream: &DataSet<Output>,
+        window: TimeWindow,
+    ) -> Result<Output, TaskError<Output>> {
+        self.run(data, window)
+    }
+
+    // Called when the runner gives up on the run for `window`, e.g. after it
+    // overran its timeout, so its output is never delivered.
+    fn discard(&self, _window: TimeWindow) {}
+}
+
+pub trait AsyncRunnable<Input, Output>: Send + Sync {
+    fn name(&self) -> String;
+    fn run<'a>(
+        &'a self,
+        data: &'a Input,
+        window: TimeWindow,
+    ) -> BoxFuture<'a, Result<Output, TaskError<Output>>>;
+
+    fn run_with_upstream<'a>(
+        &'a self,
+        data: &'a Input,
+        _upstream: &'a DataSet<Output>,
+        window: TimeWindow,
+    ) -> BoxFuture<'a, Result<Output, TaskError<Output>>> {
+        self.run(data, window)
+    }
+
+    fn discard(&self, _window: TimeWindow) {}
+}
+
+pub enum Task<Input, Output> {
+    Sync(Arc<dyn Runnable<Input, Output>>),
+    Async(Arc<dyn AsyncRunnable<Input, Output>>),
+}
+
+impl<Input, Output> Task<Input, Output> {
+    pub fn name(&self) -> String {
+        match self {
+            Task::Sync(task) => task.name(),
+            Task::Async(task) => task.name(),
+        }
+    }
+
+    pub async fn run(&self, data: &Input, window: TimeWindow) -> Result<Output, TaskError<Output>> {
+        match self {
+            Task::Sync(task) => task.run(data, window),
+            Task::Async(task) => task.run(data, window).await,
+        }
+    }
+
+    pub async fn run_with_upstream(
+        &self,
+        data: &Input,
+        upstream: &DataSet<Output>,
+        window: TimeWindow,
+    ) -> Result<Output, TaskError<Output>> {
+        match self {
+            Task::Sync(task) => task.run_with_upstream(data, upstream, window),
+            Task::Async(task) => task.run_with_upstream(data, upstream, window).await,
+        }
+    }
+
+    pub fn discard(&self, window: TimeWindow) {
+        match self {
+            Task::Sync(task) => task.discard(window),
+            Task::Async(task) => task.discard(window),
+        }
+    }
+}
+
+impl<Input, Output> Clone for Task<Input, Output> {
+    fn clone(&self) -> Self {
+        match self {
+            Task::Sync(task) => Task::Sync(Arc::clone(task)),
+            Task::Async(task) => Task::Async(Arc::clone(task)),
+        }
+    }
+}
+
+pub(crate) struct Typed<R, T> {
+    task: R,
+    _output: PhantomData<fn() -> T>,
+}
+
+impl<R, T> Typed<R, T> {
+    pub(crate) fn new(task: R) -> Self {
+        Typed {
+            task,
+            _output: PhantomData,
+        }
+    }
+}
+
+impl<Input, T, R> Runnable<Input, AnyOutput> for Typed<R, T>
+where
+    R: Runnable<Input, T>,
+    T: Send + Sync + 'static,
+{
+    fn name(&self) -> String {
+        self.task.name()
+    }
+
+    fn run(&self, data: &Input, window: TimeWindow) -> Result<AnyOutput, TaskError<AnyOutput>> {
+        match self.task.run(data, window) {
+            Ok(output) => Ok(Box::new(output)),
+            Err(e) => Err(e.map_output()),
+        }
+    }
+
+    fn discard(&self, window: TimeWindow) {
+        self.task.discard(window)
+    }
+}
+
+impl<Input, T, R> AsyncRunnable<Input, AnyOutput> for Typed<R, T>
+where
+    Input: Sync,
+    R: AsyncRunnable<Input, T>,
+    T: Send + Sync + 'static,
+{
+    fn name(&self) -> String {
+        self.task.name()
+    }
+
+    fn run<'a>(
+        &'a self,
+        data: &'a Input,
+        window: TimeWindow,
+    ) -> BoxFuture<'a, Result<AnyOutput, TaskError<AnyOutput>>> {
+        Box::pin(async move {
+            match self.task.run(data, window).await {
+                Ok(output) => Ok(Box::new(output) as AnyOutput),
+                Err(e) => Err(e.map_output()),
+            }
+        })
+    }
+
+    fn discard(&self, window: TimeWindow) {
+        self.task.discard(window)
+    }
+}
+
+pub struct TaskSpec<Input, Output> {
+    pub task: Task<Input, Output>,
+    pub config: TaskConfig,
+}
+
+impl<Input, Output> TaskSpec<Input, Output> {
+    pub fn new(task: Task<Input, Output>, config: TaskConfig) -> Self {
+        TaskSpec { task, config }
+    }
+}
+
+impl<Input, Output> Clone for TaskSpec<Input, Output> {
+    fn clone(&self) -> Self {
+        TaskSpec {
+            task: self.task.clone(),
+            config: self.config.clone(),
+        }
+    }
+}
+
+enum Outcome<Output> {
+    Completed(Output),
+    Unavailable(TaskStatus),
+    Failed(TaskError<Output>),
 }
 
 pub struct Worker<Input, Output> {
-    task: Arc<dyn Runnable<Input, Output>>,
+    name: String,
+    disabled: bool,
+    task: Task<Input, Output>,
+    config: TaskConfig,
     ctx: TaskContext<Input, Output>,
 }
 
 pub struct TaskContext<Input, Output> {
     pub data: Arc<RwLock<Input>>,
-    pub receiver: broadcast::Receiver<(u64, u64)>,
+    pub receiver: broadcast::Receiver<Tick<Input, Output>>,
     pub sender: mpsc::Sender<TaskResult<Output>>,
+    pub cadence: usize,
+}
+
+// A tick runs one dependency layer at a time; `tasks` names the layer and
+// `upstream` holds the outputs of the layers before it.
+pub struct Tick<Input, Output> {
+    pub id: u64,
+    pub cadence: usize,
+    pub window: TimeWindow,
+    pub input: Option<Arc<Input>>,
+    pub tasks: Arc<Vec<String>>,
+    pub upstream: Arc<DataSet<Output>>,
+}
+
+impl<Input, Output> Clone for Tick<Input, Output> {
+    fn clone(&self) -> Self {
+        Tick {
+            id: self.id,
+            cadence: self.cadence,
+            window: self.window,
+            input: self.input.clone(),
+            tasks: Arc::clone(&self.tasks),
+            upstream: Arc::clone(&self.upstream),
+        }
+    }
+}
+
+// What a task reads for one tick: either the shared input under its read
+// lock, or the snapshot the runner took for the tick.
+enum InputRef<Input> {
+    Locked(OwnedRwLockReadGuard<Input>),
+    Snapshot(Arc<Input>),
+}
+
+impl<Input> Deref for InputRef<Input> {
+    type Target = Input;
+
+    fn deref(&self) -> &Input {
+        match self {
+            InputRef::Locked(guard) => guard,
+            InputRef::Snapshot(snapshot) => snapshot,
+        }
+    }
 }
 
 pub struct TaskResult<Output> {
     pub name: String,
+    pub tick: u64,
+    pub window: TimeWindow,
+    pub status: TaskStatus,
     pub result: Option<Output>,
 }
 
-impl<Input, Output> Worker<Input, Output> {
-    pub fn new(task: Arc<dyn Runnable<Input, Output>>, ctx: TaskContext<Input, Output>) -> Self {
-        Worker { task, ctx }
+impl<Output> TaskResult<Output> {
+    pub fn new(
+        name: &str,
+        tick: u64,
+        window: TimeWindow,
+        status: TaskStatus,
+        result: Option<Output>,
+    ) -> Self {
+        TaskResult {
+            name: name.to_string(),
+            tick,
+            window,
+            status,
+            result,
+        }
+    }
+}
+
+impl<Input: Send + Sync + 'static, Output: Send + Sync + 'static> Worker<Input, Output> {
+    pub fn new(spec: TaskSpec<Input, Output>, ctx: TaskContext<Input, Output>) -> Self {
+        Worker {
+            name: spec.task.name(),
+            disabled: false,
+            task: spec.task,
+            config: spec.config,
+            ctx,
+        }
+    }
+
+    #[instrument(skip(self), fields(task_name = %self.name))]
+    pub async fn run(&mut self, timeout_duration: Duration) -> Result<(), TaskError<Output>> {
+        debug!("Starting worker task");
+
+        loop {
+            match self.ctx.receiver.recv().await {
+                Ok(tick)
+                    if tick.cadence != self.ctx.cadence || !tick.tasks.contains(&self.name) =>
+                {
+                    continue
+                }
+                Ok(tick) => self.process(&tick, timeout_duration).await?,
+                Err(RecvError::Closed) => {
+                    info!("Time window channel closed, stopping worker");
+                    break Ok(());
+                }
+                Err(RecvError::Lagged(missed)) => {
+                    warn!(missed = %missed, "Worker fell behind, skipping to the latest ticks");
+                    continue;
+                }
+            }
+        }
     }
 
-    #[instrument(skip(self, shutdown_rx), fields(task_name = %self.task.name()))]
-    pub async fn run(
+    async fn process(
         &mut self,
-        mut shutdown_rx: broadcast::Receiver<()>,
+        tick: &Tick<Input, Output>,
         timeout_duration: Duration,
     ) -> Result<(), TaskError<Output>> {
-        let name = self.task.name().clone();
-        debug!("Starting worker task");
+        debug!(tick = %tick.id, window = %tick.window, "Processing time window");
 
+        if self.disabled {
+            debug!("Task is disabled, skipping time window");
+            return self
+                .send_status(tick, TaskStatus::Skipped("task disabled".to_string()))
+                .await;
+        }
+
+        let mut retries = 0;
         loop {
-            tokio::select! {
-                _ = shutdown_rx.recv() => {
-                    info!("Received shutdown signal");
-                    break Ok(());
+            let error = match self.attempt(tick, timeout_duration).await {
+                Outcome::Completed(result) => {
+                    debug!("Task completed successfully");
+                    return self
+                        .send(TaskResult::new(
+                            &self.name,
+                            tick.id,
+                            tick.window,
+                            TaskStatus::Ok,
+                            Some(result),
+                        ))
+                        .await;
                 }
-                result = self.ctx.receiver.recv() => {
-                    match result {
-                        Ok((start, end)) => {
-                            debug!(start = %start, end = %end, "Processing time window");
-
-                            let data = match time::timeout(
-                                timeout_duration,
-                                self.ctx.data.read(),
-                            ).await {
-                                Ok(guard) => guard,
-                                Err(_) => {
-                                    error!("Data read timeout, abandoning current work");
-                                    if let Err(e) = self.ctx.sender.send(TaskResult {
-                                        name: name.clone(),
-                                        result: None,
-                                    }).await {
-                                        error!(error = %e, "Failed to send task result");
-                                        return Err(e.into());
-                                    }
-                                    continue;
-                                }
-                            };
-
-                            match self.task.run(&*data, start, end) {
-                                Ok(result) => {
-                                    debug!("Task completed successfully");
-                                    if let Err(e) = self.ctx.sender.send(TaskResult {
-                                        name: name.clone(),
-                                        result: Some(result),
-                                    }).await {
-                                        error!(error = %e, "Failed to send task result");
-                                        return Err(e.into());
-                                    }
-                                }
-                                Err(e) => {
-                                    error!(error = %e, "Task execution failed");
-                                    return Err(e);
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            error!(error = %e, "Failed to receive time window");
-                            return Err(TaskError::RecvError(e));
-                        }
+                Outcome::Unavailable(status) => return self.send_status(tick, status).await,
+                Outcome::Failed(e) => e,
+            };
+
+            error!(error = %error, retries = %retries, "Task execution failed");
+            match self.config.on_failure {
+                FailurePolicy::Retry {
+                    max_retries,
+                    backoff,
+                } if retries < max_retries => {
+                    let delay = backoff.saturating_mul(2u32.saturating_pow(retries as u32));
+                    retries += 1;
+                    warn!(delay_ms = %delay.as_millis(), "Retrying task");
+                    time::sleep(delay).await;
+                }
+                FailurePolicy::Abort => {
+                    self.send_status(tick, TaskStatus::Failed(error.to_string()))
+                        .await?;
+                    return Err(error);
+                }
+                FailurePolicy::Disable => {
+                    warn!("Disabling task after failure");
+                    self.disabled = true;
+                    return self
+                        .send_status(tick, TaskStatus::Failed(error.to_string()))
+                        .await;
+                }
+                _ => {
+                    return self
+                        .send_status(tick, TaskStatus::Failed(error.to_string()))
+                        .await;
+                }
+            }
+        }
+    }
+
+    async fn attempt(
+        &self,
+        tick: &Tick<Input, Output>,
+        timeout_duration: Duration,
+    ) -> Outcome<Output> {
+        let started = Instant::now();
+        let lock_timeout = match self.config.timeout {
+            Some(limit) => limit.min(timeout_duration),
+            None => timeout_duration,
+        };
+        let data = match &tick.input {
+            Some(snapshot) => InputRef::Snapshot(Arc::clone(snapshot)),
+            None => {
+                match time::timeout(lock_timeout, Arc::clone(&self.ctx.data).read_owned()).await {
+                    Ok(guard) => InputRef::Locked(guard),
+                    Err(_) => {
+                        error!("Data read timeout, abandoning current work");
+                        return Outcome::Unavailable(TaskStatus::LockTimeout);
                     }
                 }
             }
+        };
+
+        let execution = match self.config.timeout {
+            Some(limit) => {
+                let remaining = limit.saturating_sub(started.elapsed());
+                time::timeout(remaining, self.execute(data, tick))
+                    .await
+                    .ok()
+            }
+            None => Some(self.execute(data, tick).await),
+        };
+        let timed_out = self
+            .config
+            .timeout
+            .is_some_and(|limit| started.elapsed() > limit);
+
+        match execution {
+            Some(Ok(result)) if !timed_out => Outcome::Completed(result),
+            Some(Err(e)) => Outcome::Failed(e),
+            _ => {
+                warn!(elapsed_ms = %started.elapsed().as_millis(), "Task exceeded its timeout, discarding result");
+                self.task.discard(tick.window);
+                Outcome::Unavailable(TaskStatus::TimedOut)
+            }
+        }
+    }
+
+    async fn send_status(
+        &self,
+        tick: &Tick<Input, Output>,
+        status: TaskStatus,
+    ) -> Result<(), TaskError<Output>> {
+        self.send(TaskResult::new(
+            &self.name,
+            tick.id,
+            tick.window,
+            status,
+            None,
+        ))
+        .await
+    }
+
+    async fn send(&self, result: TaskResult<Output>) -> Result<(), TaskError<Output>> {
+        self.ctx.sender.send(result).await.map_err(|e| {
+            error!(error = %e, "Failed to send task result");
+            e.into()
+        })
+    }
+
+    async fn execute(
+        &self,
+        data: InputRef<Input>,
+        tick: &Tick<Input, Output>,
+    ) -> Result<Output, TaskError<Output>> {
+        let window = tick.window;
+        let upstream = Arc::clone(&tick.upstream);
+        match (&self.task, &self.config.mode) {
+            (Task::Sync(task), ExecutionMode::Pool(pool)) => {
+                let task = Arc::clone(task);
+                pool.spawn(move || task.run_with_upstream(&*data, &upstream, window))
+                    .await
+                    .map_err(|_| TaskError::TaskError("Task pool dropped the task".to_string()))?
+            }
+            // A timeout can't preempt a sync task running inline, so such a task
+            // runs on the blocking pool instead.
+            (Task::Sync(task), mode)
+                if matches!(mode, ExecutionMode::Blocking) || self.config.timeout.is_some() =>
+            {
+                let task = Arc::clone(task);
+                tokio::task::spawn_blocking(move || {
+                    task.run_with_upstream(&*data, &upstream, window)
+                })
+                .await?
+            }
+            (task, _) => {
+                CatchUnwind(Box::pin(task.run_with_upstream(&*data, &upstream, window))).await
+            }
+        }
+    }
+}
+
+// Reports a panic in an inline task as a failure, the way `spawn_blocking`
+// and the task pool do, instead of taking the worker down with it.
+struct CatchUnwind<F>(Pin<Box<F>>);
+
+impl<F, Output> Future for CatchUnwind<F>
+where
+    F: Future<Output = Result<Output, TaskError<Output>>>,
+{
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let task = &mut self.get_mut().0;
+        match panic::catch_unwind(AssertUnwindSafe(|| task.as_mut().poll(cx))) {
+            Ok(poll) => poll,
+            Err(payload) => {
+                let message = payload
+                    .downcast_ref::<&str>()
+                    .map(|message| message.to_string())
+                    .or_else(|| payload.downcast_ref::<String>().cloned())
+                    .unwrap_or_else(|| "unknown panic".to_string());
+                error!(panic = %message, "Task panicked");
+                Poll::Ready(Err(TaskError::TaskError(format!(
+                    "Task panicked: {}",
+                    message
+                ))))
+            }
         }
     }
 }
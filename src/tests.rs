@@ -1,6 +1,9 @@
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod tests {
     use crate::prelude::*;
+    use chrono::{TimeZone, Utc};
+    use std::sync::atomic::{AtomicUsize, Ordering};
     use std::sync::Arc;
     use tokio::sync::RwLock;
     use tokio::time::Duration;
@@ -10,7 +13,7 @@ mod tests {
         value: i32,
     }
 
-    #[derive(Debug, PartialEq)]
+    #[derive(Clone, Debug, PartialEq)]
     struct TestResult {
         value: i32,
     }
@@ -28,8 +31,7 @@ mod tests {
         fn run(
             &self,
             data: &TestData,
-            _start: u64,
-            _end: u64,
+            _window: TimeWindow,
         ) -> Result<TestResult, TaskError<TestResult>> {
             Ok(TestResult {
                 value: data.value * self.multiplier,
@@ -37,6 +39,84 @@ mod tests {
         }
     }
 
+    struct AsyncTestTask {
+        offset: i32,
+    }
+
+    impl AsyncRunnable<TestData, TestResult> for AsyncTestTask {
+        fn name(&self) -> String {
+            format!("AsyncTestTask_{}", self.offset)
+        }
+
+        fn run<'a>(
+            &'a self,
+            data: &'a TestData,
+            _window: TimeWindow,
+        ) -> BoxFuture<'a, Result<TestResult, TaskError<TestResult>>> {
+            Box::pin(async move {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                Ok(TestResult {
+                    value: data.value + self.offset,
+                })
+            })
+        }
+    }
+
+    struct SlowTask {
+        delay: Duration,
+    }
+
+    impl Runnable<TestData, TestResult> for SlowTask {
+        fn name(&self) -> String {
+            "SlowTask".to_string()
+        }
+
+        fn run(
+            &self,
+            data: &TestData,
+            _window: TimeWindow,
+        ) -> Result<TestResult, TaskError<TestResult>> {
+            std::thread::sleep(self.delay);
+            Ok(TestResult { value: data.value })
+        }
+    }
+
+    struct FlakyTask {
+        failures: usize,
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl Runnable<TestData, TestResult> for FlakyTask {
+        fn name(&self) -> String {
+            "FlakyTask".to_string()
+        }
+
+        fn run(
+            &self,
+            data: &TestData,
+            _window: TimeWindow,
+        ) -> Result<TestResult, TaskError<TestResult>> {
+            if self.calls.fetch_add(1, Ordering::SeqCst) < self.failures {
+                return Err(TaskError::TaskError("flaky".to_string()));
+            }
+            Ok(TestResult { value: data.value })
+        }
+    }
+
+    struct AlertTask {
+        threshold: i32,
+    }
+
+    impl Runnable<TestData, bool> for AlertTask {
+        fn name(&self) -> String {
+            "AlertTask".to_string()
+        }
+
+        fn run(&self, data: &TestData, _window: TimeWindow) -> Result<bool, TaskError<bool>> {
+            Ok(data.value > self.threshold)
+        }
+    }
+
     #[tokio::test]
     async fn test_single_task() {
         let data = Arc::new(RwLock::new(TestData { value: 42 }));
@@ -86,6 +166,2160 @@ mod tests {
         assert_eq!(result.get("TestTask_3").unwrap().value, 30);
     }
 
+    #[tokio::test]
+    async fn test_sync_and_async_tasks() {
+        let data = Arc::new(RwLock::new(TestData { value: 10 }));
+
+        let (ctx, mut receiver, _) = ContextBuilder::new()
+            .with_task(TestTask { multiplier: 2 })
+            .with_async_task(AsyncTestTask { offset: 5 })
+            .with_data(data.clone())
+            .with_interval(TaskInterval::Millis(100))
+            .build();
+
+        let runner = crate::Runner::new(ctx);
+
+        tokio::spawn(async move { runner.run().await });
+
+        let result = tokio::time::timeout(Duration::from_millis(200), receiver.recv())
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(result.get("TestTask_2").unwrap().value, 20);
+        assert_eq!(result.get("AsyncTestTask_5").unwrap().value, 15);
+    }
+
+    #[tokio::test]
+    async fn test_blocking_and_pool_execution() {
+        let data = Arc::new(RwLock::new(TestData { value: 10 }));
+        let pool = Arc::new(TaskPool::new(2));
+
+        let (ctx, mut receiver, _) = ContextBuilder::new()
+            .with_task_config(
+                TestTask { multiplier: 2 },
+                TaskConfig::new(ExecutionMode::Blocking),
+            )
+            .with_task_config(
+                TestTask { multiplier: 3 },
+                TaskConfig::new(ExecutionMode::Pool(pool)),
+            )
+            .with_data(data.clone())
+            .with_interval(TaskInterval::Millis(100))
+            .build();
+
+        let runner = crate::Runner::new(ctx);
+
+        tokio::spawn(async move { runner.run().await });
+
+        let result = tokio::time::timeout(Duration::from_millis(200), receiver.recv())
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(result.get("TestTask_2").unwrap().value, 20);
+        assert_eq!(result.get("TestTask_3").unwrap().value, 30);
+    }
+
+    #[tokio::test]
+    async fn test_task_timeout() {
+        let data = Arc::new(RwLock::new(TestData { value: 10 }));
+
+        let (ctx, mut receiver, _) = ContextBuilder::new()
+            .with_task(TestTask { multiplier: 2 })
+            .with_task_config(
+                SlowTask {
+                    delay: Duration::from_millis(500),
+                },
+                TaskConfig::new(ExecutionMode::Blocking).with_timeout(Duration::from_millis(20)),
+            )
+            .with_data(data.clone())
+            .with_interval(TaskInterval::Millis(100))
+            .build();
+
+        let runner = crate::Runner::new(ctx);
+
+        tokio::spawn(async move { runner.run().await });
+
+        let result = tokio::time::timeout(Duration::from_millis(200), receiver.recv())
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(result.get("TestTask_2").unwrap().value, 20);
+        assert!(result.get("SlowTask").is_none());
+        assert_eq!(result.status("SlowTask"), Some(&TaskStatus::TimedOut));
+    }
+
+    #[tokio::test]
+    async fn test_inline_task_timeout() {
+        let (ctx, mut receiver, _) = ContextBuilder::new()
+            .with_task(TestTask { multiplier: 2 })
+            .with_task_config(
+                SlowTask {
+                    delay: Duration::from_millis(800),
+                },
+                TaskConfig::default().with_timeout(Duration::from_millis(20)),
+            )
+            .with_data(Arc::new(RwLock::new(TestData { value: 10 })))
+            .with_interval(TaskInterval::Millis(100))
+            .build();
+
+        let runner = crate::Runner::new(ctx);
+        tokio::spawn(async move { runner.run().await });
+
+        let started = std::time::Instant::now();
+        let result = tokio::time::timeout(Duration::from_millis(200), receiver.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(result.get("TestTask_2").unwrap().value, 20);
+        assert!(started.elapsed() < Duration::from_millis(200));
+        assert_eq!(result.status("SlowTask"), Some(&TaskStatus::TimedOut));
+    }
+
+    #[tokio::test]
+    async fn test_task_timeout_covers_lock_wait() {
+        let data = Arc::new(RwLock::new(TestData { value: 10 }));
+
+        let (ctx, mut receiver, _) = ContextBuilder::new()
+            .with_task_config(
+                TestTask { multiplier: 2 },
+                TaskConfig::default().with_timeout(Duration::from_millis(100)),
+            )
+            .with_data(data.clone())
+            .with_interval(TaskInterval::Seconds(1))
+            .build();
+
+        let writer = data.write().await;
+        let runner = crate::Runner::new(ctx);
+        let handle = runner.handle();
+        let runner_handle = tokio::spawn(async move { runner.run().await });
+
+        // Well within the 5s lock timeout of the default config.
+        let result = tokio::time::timeout(Duration::from_millis(1000), receiver.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(matches!(
+            result.status("TestTask_2"),
+            Some(TaskStatus::LockTimeout | TaskStatus::TimedOut)
+        ));
+
+        drop(writer);
+        handle.shutdown().unwrap();
+        runner_handle.await.unwrap().unwrap();
+    }
+
+    struct PanickingTask;
+
+    impl Runnable<TestData, TestResult> for PanickingTask {
+        fn name(&self) -> String {
+            "PanickingTask".to_string()
+        }
+
+        fn run(
+            &self,
+            _data: &TestData,
+            _window: TimeWindow,
+        ) -> Result<TestResult, TaskError<TestResult>> {
+            panic!("boom");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_panicking_task() {
+        let (ctx, mut receiver, _) = ContextBuilder::new()
+            .with_task(TestTask { multiplier: 2 })
+            .with_task(PanickingTask)
+            .with_data(Arc::new(RwLock::new(TestData { value: 10 })))
+            .with_interval(TaskInterval::Millis(50))
+            .build();
+
+        let runner = crate::Runner::new(ctx);
+        let handle = runner.handle();
+        let runner_handle = tokio::spawn(async move { runner.run().await });
+
+        for _ in 0..2 {
+            let result = tokio::time::timeout(Duration::from_millis(500), receiver.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(result.get("TestTask_2").unwrap().value, 20);
+            assert!(matches!(
+                result.status("PanickingTask"),
+                Some(TaskStatus::Failed(e)) if e.contains("boom")
+            ));
+        }
+
+        handle.shutdown().unwrap();
+        runner_handle.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_failure_skips_tick() {
+        let data = Arc::new(RwLock::new(TestData { value: 10 }));
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let (ctx, mut receiver, _) = ContextBuilder::new()
+            .with_task(TestTask { multiplier: 2 })
+            .with_task(FlakyTask {
+                failures: 1,
+                calls: calls.clone(),
+            })
+            .with_data(data.clone())
+            .with_interval(TaskInterval::Millis(100))
+            .build();
+
+        let runner = crate::Runner::new(ctx);
+
+        tokio::spawn(async move { runner.run().await });
+
+        let result1 = tokio::time::timeout(Duration::from_millis(200), receiver.recv())
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(result1.get("TestTask_2").unwrap().value, 20);
+        assert!(matches!(
+            result1.status("FlakyTask"),
+            Some(TaskStatus::Failed(_))
+        ));
+
+        let result2 = tokio::time::timeout(Duration::from_millis(200), receiver.recv())
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(result2.get("FlakyTask").unwrap().value, 10);
+    }
+
+    #[tokio::test]
+    async fn test_failure_retry() {
+        let data = Arc::new(RwLock::new(TestData { value: 10 }));
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let (ctx, mut receiver, _) = ContextBuilder::new()
+            .with_task_config(
+                FlakyTask {
+                    failures: 2,
+                    calls: calls.clone(),
+                },
+                TaskConfig::default().with_failure_policy(FailurePolicy::Retry {
+                    max_retries: 2,
+                    backoff: Duration::from_millis(5),
+                }),
+            )
+            .with_data(data.clone())
+            .with_interval(TaskInterval::Millis(100))
+            .build();
+
+        let runner = crate::Runner::new(ctx);
+
+        tokio::spawn(async move { runner.run().await });
+
+        let result = tokio::time::timeout(Duration::from_millis(200), receiver.recv())
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(result.get("FlakyTask").unwrap().value, 10);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_failure_abort() {
+        let data = Arc::new(RwLock::new(TestData { value: 10 }));
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let (ctx, mut receiver, _) = ContextBuilder::new()
+            .with_task(TestTask { multiplier: 2 })
+            .with_task_config(
+                FlakyTask {
+                    failures: 1,
+                    calls: calls.clone(),
+                },
+                TaskConfig::default().with_failure_policy(FailurePolicy::Abort),
+            )
+            .with_data(data.clone())
+            .with_interval(TaskInterval::Millis(100))
+            .build();
+
+        let runner = crate::Runner::new(ctx);
+        let runner_handle = tokio::spawn(async move { runner.run().await });
+
+        let result = tokio::time::timeout(Duration::from_millis(200), receiver.recv())
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert!(matches!(
+            result.status("FlakyTask"),
+            Some(TaskStatus::Failed(_))
+        ));
+        assert!(runner_handle.await.unwrap().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_lock_timeout_status() {
+        let data = Arc::new(RwLock::new(TestData { value: 10 }));
+        let config = RunnerConfig::new(1024, 16, Duration::from_millis(20));
+
+        let (ctx, mut receiver, _) = ContextBuilder::new()
+            .with_task(TestTask { multiplier: 2 })
+            .with_data(data.clone())
+            .with_interval(TaskInterval::Millis(100))
+            .with_config(config)
+            .build();
+
+        let guard = data.write().await;
+        let runner = crate::Runner::new(ctx);
+
+        tokio::spawn(async move { runner.run().await });
+
+        let result = tokio::time::timeout(Duration::from_millis(200), receiver.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        drop(guard);
+
+        assert!(result.is_empty());
+        assert!(!result.is_ok());
+        assert_eq!(result.status("TestTask_2"), Some(&TaskStatus::LockTimeout));
+        assert_eq!(result.failures().count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_missing_result_fallback() {
+        let data = Arc::new(RwLock::new(TestData { value: 10 }));
+        let calls = Arc::new(AtomicUsize::new(0));
+        let config = RunnerConfig::default()
+            .with_missing_results(MissingResultPolicy::fallback(|_| TestResult { value: -1 }));
+
+        let (ctx, mut receiver, _) = ContextBuilder::new()
+            .with_task(FlakyTask {
+                failures: 1,
+                calls: calls.clone(),
+            })
+            .with_data(data.clone())
+            .with_interval(TaskInterval::Millis(100))
+            .with_config(config)
+            .build();
+
+        let runner = crate::Runner::new(ctx);
+
+        tokio::spawn(async move { runner.run().await });
+
+        let result = tokio::time::timeout(Duration::from_millis(200), receiver.recv())
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(result.get("FlakyTask").unwrap().value, -1);
+        assert!(matches!(
+            result.status("FlakyTask"),
+            Some(TaskStatus::Failed(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_missing_result_carry_forward() {
+        let data = Arc::new(RwLock::new(TestData { value: 10 }));
+        let config = RunnerConfig::new(1024, 16, Duration::from_millis(20))
+            .with_missing_results(MissingResultPolicy::carry_forward());
+
+        let (ctx, mut receiver, _) = ContextBuilder::new()
+            .with_task(TestTask { multiplier: 2 })
+            .with_data(data.clone())
+            .with_interval(TaskInterval::Millis(100))
+            .with_config(config)
+            .build();
+
+        let runner = crate::Runner::new(ctx);
+
+        tokio::spawn(async move { runner.run().await });
+
+        let result1 = tokio::time::timeout(Duration::from_millis(200), receiver.recv())
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(result1.get("TestTask_2").unwrap().value, 20);
+
+        let guard = data.write().await;
+        let result2 = tokio::time::timeout(Duration::from_millis(200), receiver.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        drop(guard);
+
+        assert_eq!(result2.get("TestTask_2").unwrap().value, 20);
+        assert_eq!(result2.status("TestTask_2"), Some(&TaskStatus::LockTimeout));
+    }
+
+    #[tokio::test]
+    async fn test_missing_result_drop_dataset() {
+        let data = Arc::new(RwLock::new(TestData { value: 10 }));
+        let calls = Arc::new(AtomicUsize::new(0));
+        let config = RunnerConfig::default().with_missing_results(MissingResultPolicy::DropDataSet);
+
+        let (ctx, mut receiver, _) = ContextBuilder::new()
+            .with_task(FlakyTask {
+                failures: 1,
+                calls: calls.clone(),
+            })
+            .with_data(data.clone())
+            .with_interval(TaskInterval::Millis(100))
+            .with_config(config)
+            .build();
+
+        let runner = crate::Runner::new(ctx);
+
+        tokio::spawn(async move { runner.run().await });
+
+        let result = tokio::time::timeout(Duration::from_millis(300), receiver.recv())
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(result.get("FlakyTask").unwrap().value, 10);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_typed_outputs() {
+        let data = Arc::new(RwLock::new(TestData { value: 10 }));
+
+        let value = TaskKey::new("TestTask_2");
+        let alert = TaskKey::new("AlertTask");
+        let offset = TaskKey::new("AsyncTestTask_1");
+        let (ctx, mut receiver, _) = ContextBuilder::new()
+            .with_typed_task(TestTask { multiplier: 2 }, &value)
+            .with_typed_task(AlertTask { threshold: 5 }, &alert)
+            .with_typed_async_task(AsyncTestTask { offset: 1 }, &offset)
+            .with_data(data.clone())
+            .with_interval(TaskInterval::Millis(100))
+            .build();
+
+        let runner = crate::Runner::new(ctx);
+
+        tokio::spawn(async move { runner.run().await });
+
+        let mut result = tokio::time::timeout(Duration::from_millis(200), receiver.recv())
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(result.get_typed(&value).unwrap().value, 20);
+        assert!(*result.get_typed(&alert).unwrap());
+        assert_eq!(result.take_typed(&offset).unwrap().value, 11);
+        assert!(result.get_typed(&offset).is_none());
+
+        let mismatch = ContextBuilder::<TestData, AnyOutput>::new()
+            .with_typed_task(TestTask { multiplier: 3 }, &value)
+            .try_build();
+        assert_eq!(
+            mismatch.err(),
+            Some(BuildError::KeyMismatch {
+                key: "TestTask_2".to_string(),
+                task: "TestTask_3".to_string(),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dynamic_tasks() {
+        let data = Arc::new(RwLock::new(TestData { value: 10 }));
+
+        let (ctx, mut receiver, _) = ContextBuilder::new()
+            .with_task(TestTask { multiplier: 2 })
+            .with_data(data.clone())
+            .with_interval(TaskInterval::Millis(50))
+            .build();
+
+        let runner = crate::Runner::new(ctx);
+        let handle = runner.handle();
+
+        tokio::spawn(async move { runner.run().await });
+
+        let result1 = tokio::time::timeout(Duration::from_millis(200), receiver.recv())
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(result1.len(), 1);
+
+        handle.add_task(TestTask { multiplier: 3 }).unwrap();
+        handle
+            .replace_task("TestTask_2", TestTask { multiplier: 4 })
+            .unwrap();
+
+        let result2 = tokio::time::timeout(Duration::from_millis(200), receiver.recv())
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(result2.len(), 2);
+        assert_eq!(result2.get("TestTask_3").unwrap().value, 30);
+        assert_eq!(result2.get("TestTask_4").unwrap().value, 40);
+        assert!(result2.status("TestTask_2").is_none());
+
+        handle.remove_task("TestTask_3").unwrap();
+        // Changes that would leave two tasks with the same name are skipped.
+        let calls: Vec<_> = (0..3).map(|_| Arc::new(AtomicUsize::new(0))).collect();
+        let flaky = |calls: &Arc<AtomicUsize>| FlakyTask {
+            failures: 0,
+            calls: Arc::clone(calls),
+        };
+        handle.add_task(flaky(&calls[0])).unwrap();
+        handle.add_task(flaky(&calls[1])).unwrap();
+        handle.replace_task("TestTask_4", flaky(&calls[2])).unwrap();
+
+        let result3 = tokio::time::timeout(Duration::from_millis(200), receiver.recv())
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(result3.len(), 2);
+        assert_eq!(result3.get("TestTask_4").unwrap().value, 40);
+        assert_eq!(result3.get("FlakyTask").unwrap().value, 10);
+        assert!(calls[0].load(Ordering::SeqCst) >= 1);
+        assert_eq!(calls[1].load(Ordering::SeqCst), 0);
+        assert_eq!(calls[2].load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_pause_resume_run_now() {
+        let data = Arc::new(RwLock::new(TestData { value: 10 }));
+
+        let (ctx, mut receiver, _) = ContextBuilder::new()
+            .with_task(TestTask { multiplier: 2 })
+            .with_data(data.clone())
+            .with_interval(TaskInterval::Millis(50))
+            .build();
+
+        let runner = crate::Runner::new(ctx);
+        let handle = runner.handle();
+        handle.pause().unwrap();
+
+        tokio::spawn(async move { runner.run().await });
+
+        assert!(
+            tokio::time::timeout(Duration::from_millis(150), receiver.recv())
+                .await
+                .is_err()
+        );
+
+        let window = TimeWindow::new(
+            Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2024, 1, 1, 0, 1, 0).unwrap(),
+        );
+        handle.run_now(Some(window)).unwrap();
+        let result1 = tokio::time::timeout(Duration::from_millis(100), receiver.recv())
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(result1.window, window);
+        assert_eq!(result1.get("TestTask_2").unwrap().value, 20);
+
+        handle.resume().unwrap();
+        let result2 = tokio::time::timeout(Duration::from_millis(100), receiver.recv())
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(result2.get("TestTask_2").unwrap().value, 20);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_pause_reports_skipped_windows() {
+        let origin = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let at = |secs| origin + chrono::Duration::seconds(secs);
+
+        let (ctx, mut receiver, _) = ContextBuilder::<TestData, TestResult>::new()
+            .with_task(TestTask { multiplier: 2 })
+            .with_interval(TaskInterval::Seconds(5))
+            .with_clock(TokioClock::new(origin))
+            .build();
+
+        let runner = crate::Runner::new(ctx);
+        let handle = runner.handle();
+        let runner_handle = tokio::spawn(async move { runner.run().await });
+
+        let first = receiver.recv().await.unwrap();
+        assert_eq!(first.window, TimeWindow::new(at(-5), at(0)));
+        assert_eq!(first.skipped, None);
+
+        // The ticks at 5s and 10s pass while paused.
+        handle.pause().unwrap();
+        tokio::time::sleep(Duration::from_secs(12)).await;
+        handle.resume().unwrap();
+
+        let resumed = receiver.recv().await.unwrap();
+        assert_eq!(resumed.window, TimeWindow::new(at(10), at(15)));
+        assert_eq!(resumed.skipped, Some(TimeWindow::new(at(0), at(10))));
+        let next = receiver.recv().await.unwrap();
+        assert_eq!(next.skipped, None);
+
+        handle.shutdown().unwrap();
+        runner_handle.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_graceful_shutdown_drains_tick() {
+        let data = Arc::new(RwLock::new(TestData { value: 10 }));
+
+        let (ctx, mut receiver, _) = ContextBuilder::new()
+            .with_task(TestTask { multiplier: 2 })
+            .with_task_config(
+                SlowTask {
+                    delay: Duration::from_millis(100),
+                },
+                TaskConfig::new(ExecutionMode::Blocking),
+            )
+            .with_data(data.clone())
+            .with_interval(TaskInterval::Seconds(1))
+            .build();
+
+        let runner = crate::Runner::new(ctx);
+        let handle = runner.handle();
+        let runner_handle = tokio::spawn(async move { runner.run().await });
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        handle.shutdown().unwrap();
+
+        let result = tokio::time::timeout(Duration::from_millis(300), receiver.recv())
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(result.get("TestTask_2").unwrap().value, 20);
+        assert_eq!(result.get("SlowTask").unwrap().value, 10);
+        assert!(runner_handle.await.unwrap().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_graceful_shutdown_reports_unfinished() {
+        let data = Arc::new(RwLock::new(TestData { value: 10 }));
+        let config = RunnerConfig::new(1024, 16, Duration::from_millis(20));
+
+        let (ctx, mut receiver, _) = ContextBuilder::new()
+            .with_task(TestTask { multiplier: 2 })
+            .with_task_config(
+                SlowTask {
+                    delay: Duration::from_millis(300),
+                },
+                TaskConfig::new(ExecutionMode::Blocking),
+            )
+            .with_data(data.clone())
+            .with_interval(TaskInterval::Seconds(1))
+            .with_config(config)
+            .build();
+
+        let runner = crate::Runner::new(ctx);
+        let handle = runner.handle();
+        let runner_handle = tokio::spawn(async move { runner.run().await });
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        handle.shutdown().unwrap();
+
+        let result = tokio::time::timeout(Duration::from_millis(200), receiver.recv())
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(result.get("TestTask_2").unwrap().value, 20);
+        assert_eq!(result.status("SlowTask"), Some(&TaskStatus::Cancelled));
+        assert!(runner_handle.await.unwrap().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_abort_drops_tick() {
+        let data = Arc::new(RwLock::new(TestData { value: 10 }));
+
+        let (ctx, mut receiver, _) = ContextBuilder::new()
+            .with_task_config(
+                SlowTask {
+                    delay: Duration::from_millis(100),
+                },
+                TaskConfig::new(ExecutionMode::Blocking),
+            )
+            .with_data(data.clone())
+            .with_interval(TaskInterval::Seconds(1))
+            .build();
+
+        let runner = crate::Runner::new(ctx);
+        let handle = runner.handle();
+        let runner_handle = tokio::spawn(async move { runner.run().await });
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        handle.abort().unwrap();
+
+        let finished = tokio::time::timeout(Duration::from_millis(50), runner_handle)
+            .await
+            .unwrap();
+        assert!(finished.unwrap().is_ok());
+        assert!(receiver.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_abort_during_drain() {
+        let data = Arc::new(RwLock::new(TestData { value: 10 }));
+        let config = RunnerConfig::new(1024, 16, Duration::from_secs(5));
+
+        let (ctx, mut receiver, _) = ContextBuilder::new()
+            .with_task_config(
+                SlowTask {
+                    delay: Duration::from_secs(2),
+                },
+                TaskConfig::new(ExecutionMode::Blocking),
+            )
+            .with_data(data.clone())
+            .with_interval(TaskInterval::Seconds(1))
+            .with_config(config)
+            .build();
+
+        let runner = crate::Runner::new(ctx);
+        let handle = runner.handle();
+        let runner_handle = tokio::spawn(async move { runner.run().await });
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        handle.shutdown().unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        handle.abort().unwrap();
+
+        let finished = tokio::time::timeout(Duration::from_millis(100), runner_handle)
+            .await
+            .unwrap();
+        assert!(finished.unwrap().is_ok());
+        assert!(receiver.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_timeout_covers_drain_and_join() {
+        let data = Arc::new(RwLock::new(TestData { value: 10 }));
+        let config = RunnerConfig::new(1024, 16, Duration::from_millis(200));
+
+        let (ctx, mut receiver, _) = ContextBuilder::new()
+            .with_task_config(
+                SlowTask {
+                    delay: Duration::from_secs(2),
+                },
+                TaskConfig::new(ExecutionMode::Blocking),
+            )
+            .with_data(data.clone())
+            .with_interval(TaskInterval::Seconds(1))
+            .with_config(config)
+            .build();
+
+        let runner = crate::Runner::new(ctx);
+        let handle = runner.handle();
+        let runner_handle = tokio::spawn(async move { runner.run().await });
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let started = std::time::Instant::now();
+        handle.shutdown().unwrap();
+
+        let finished = tokio::time::timeout(Duration::from_millis(350), runner_handle)
+            .await
+            .unwrap();
+        assert!(finished.unwrap().is_ok());
+        assert!(started.elapsed() < Duration::from_millis(300));
+
+        let result = receiver.recv().await.unwrap();
+        assert_eq!(result.status("SlowTask"), Some(&TaskStatus::Cancelled));
+    }
+
+    struct DiscardingTask {
+        delay: Duration,
+        discarded: Arc<std::sync::Mutex<Vec<TimeWindow>>>,
+    }
+
+    impl Runnable<TestData, TestResult> for DiscardingTask {
+        fn name(&self) -> String {
+            "DiscardingTask".to_string()
+        }
+
+        fn run(
+            &self,
+            data: &TestData,
+            _window: TimeWindow,
+        ) -> Result<TestResult, TaskError<TestResult>> {
+            std::thread::sleep(self.delay);
+            Ok(TestResult { value: data.value })
+        }
+
+        fn discard(&self, window: TimeWindow) {
+            self.discarded.lock().unwrap().push(window);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_task_is_discarded() {
+        let discarded = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let (ctx, mut receiver, _) = ContextBuilder::new()
+            .with_task_config(
+                DiscardingTask {
+                    delay: Duration::from_millis(400),
+                    discarded: Arc::clone(&discarded),
+                },
+                TaskConfig::new(ExecutionMode::Blocking),
+            )
+            .with_data(Arc::new(RwLock::new(TestData { value: 10 })))
+            .with_interval(TaskInterval::Seconds(1))
+            .with_config(RunnerConfig::new(1024, 16, Duration::from_millis(100)))
+            .build();
+
+        let runner = crate::Runner::new(ctx);
+        let handle = runner.handle();
+        let runner_handle = tokio::spawn(async move { runner.run().await });
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        handle.shutdown().unwrap();
+        runner_handle.await.unwrap().unwrap();
+
+        // The runner gave up on the run, so the task hears about it.
+        let result = receiver.recv().await.unwrap();
+        assert_eq!(
+            result.status("DiscardingTask"),
+            Some(&TaskStatus::Cancelled)
+        );
+        assert_eq!(*discarded.lock().unwrap(), vec![result.window]);
+    }
+
+    #[test]
+    fn test_cron_schedule() {
+        let cron: CronSchedule = "*/5 9-17 * * 1-5".parse().unwrap();
+        // 2024-01-05 is a Friday
+        let friday = Utc.with_ymd_and_hms(2024, 1, 5, 17, 56, 30).unwrap();
+        assert_eq!(
+            cron.next_after(friday),
+            Some(Utc.with_ymd_and_hms(2024, 1, 8, 9, 0, 0).unwrap())
+        );
+        assert_eq!(
+            cron.prev_before(friday),
+            Some(Utc.with_ymd_and_hms(2024, 1, 5, 17, 55, 0).unwrap())
+        );
+
+        let daily = CronSchedule::parse("0 0 * * *").unwrap();
+        let midnight = Utc.with_ymd_and_hms(2024, 2, 29, 0, 0, 0).unwrap();
+        assert_eq!(
+            daily.next_after(midnight),
+            Some(Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap())
+        );
+        assert_eq!(daily.to_string(), "0 0 * * *");
+
+        assert!(CronSchedule::parse("0 0 31 2 *")
+            .unwrap()
+            .next_after(midnight)
+            .is_none());
+        assert!(CronSchedule::parse("* * *").is_err());
+        assert!(CronSchedule::parse("60 * * * *").is_err());
+        assert!(CronSchedule::parse("*/0 * * * *").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_aligned_schedule() {
+        let data = Arc::new(RwLock::new(TestData { value: 10 }));
+
+        let (ctx, mut receiver, _) = ContextBuilder::new()
+            .with_task(TestTask { multiplier: 2 })
+            .with_data(data)
+            .with_schedule(Schedule::Aligned(TaskInterval::Millis(100)))
+            .build();
+
+        let runner = crate::Runner::new(ctx);
+        let handle = runner.handle();
+        let runner_handle = tokio::spawn(async move { runner.run().await });
+
+        for _ in 0..2 {
+            let dataset = tokio::time::timeout(Duration::from_millis(300), receiver.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(dataset.window.end.timestamp_subsec_millis() % 100, 0);
+            assert_eq!(
+                dataset.window.duration(),
+                chrono::Duration::milliseconds(100)
+            );
+        }
+
+        handle.shutdown().unwrap();
+        runner_handle.await.unwrap().unwrap();
+
+        // The current window follows the given clock, not the wall clock.
+        let at = |min, sec| Utc.with_ymd_and_hms(2024, 1, 1, 12, min, sec).unwrap();
+        let clock = ManualClock::new(at(7, 30));
+        assert_eq!(
+            Schedule::Aligned(TaskInterval::Minutes(5)).current_window(&clock),
+            TimeWindow::new(at(0, 0), at(5, 0))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_per_task_schedule() {
+        let data = Arc::new(RwLock::new(TestData { value: 10 }));
+        let slow = Schedule::Interval(TaskInterval::Millis(300));
+
+        let (ctx, mut receiver, _) = ContextBuilder::new()
+            .with_task(TestTask { multiplier: 2 })
+            .with_task_config(
+                TestTask { multiplier: 3 },
+                TaskConfig::default().with_schedule(slow.clone()),
+            )
+            .with_data(data)
+            .with_interval(TaskInterval::Millis(100))
+            .build();
+
+        let runner = crate::Runner::new(ctx);
+        let handle = runner.handle();
+        let runner_handle = tokio::spawn(async move { runner.run().await });
+
+        let (mut fast_ticks, mut slow_ticks) = (0, 0);
+        let deadline = tokio::time::Instant::now() + Duration::from_millis(650);
+        while let Ok(Some(dataset)) = tokio::time::timeout_at(deadline, receiver.recv()).await {
+            assert_eq!(dataset.len(), 1);
+            if dataset.schedule.as_ref() == Some(&slow) {
+                assert_eq!(dataset.get("TestTask_3").unwrap().value, 30);
+                slow_ticks += 1;
+            } else {
+                assert_eq!(dataset.get("TestTask_2").unwrap().value, 20);
+                fast_ticks += 1;
+            }
+        }
+        assert!(fast_ticks >= 5, "fast ticks: {}", fast_ticks);
+        assert!((2..=3).contains(&slow_ticks), "slow ticks: {}", slow_ticks);
+
+        handle.shutdown().unwrap();
+        runner_handle.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_slow_cadence_does_not_delay_others() {
+        let data = Arc::new(RwLock::new(TestData { value: 10 }));
+        let slow = Schedule::Interval(TaskInterval::Millis(200));
+
+        let (ctx, mut receiver, _) = ContextBuilder::new()
+            .with_task(TestTask { multiplier: 2 })
+            .with_task_config(
+                SlowTask {
+                    delay: Duration::from_millis(600),
+                },
+                TaskConfig::new(ExecutionMode::Blocking).with_schedule(slow.clone()),
+            )
+            .with_data(data)
+            .with_interval(TaskInterval::Millis(100))
+            .build();
+
+        let runner = crate::Runner::new(ctx);
+        let handle = runner.handle();
+        let runner_handle = tokio::spawn(async move { runner.run().await });
+
+        let (mut fast_ticks, mut slow_ticks) = (0, 0);
+        let mut last_fast = tokio::time::Instant::now();
+        let mut longest_gap = Duration::ZERO;
+        let deadline = tokio::time::Instant::now() + Duration::from_millis(1000);
+        while let Ok(Some(dataset)) = tokio::time::timeout_at(deadline, receiver.recv()).await {
+            if dataset.schedule.as_ref() == Some(&slow) {
+                assert_eq!(dataset.get("SlowTask").unwrap().value, 10);
+                slow_ticks += 1;
+            } else {
+                assert_eq!(dataset.get("TestTask_2").unwrap().value, 20);
+                longest_gap = longest_gap.max(last_fast.elapsed());
+                last_fast = tokio::time::Instant::now();
+                fast_ticks += 1;
+            }
+        }
+        assert!(slow_ticks >= 1, "slow ticks: {}", slow_ticks);
+        assert!(fast_ticks >= 8, "fast ticks: {}", fast_ticks);
+        assert!(
+            longest_gap < Duration::from_millis(250),
+            "longest gap: {:?}",
+            longest_gap
+        );
+
+        handle.shutdown().unwrap();
+        runner_handle.await.unwrap().unwrap();
+    }
+
+    #[test]
+    fn test_time_window() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let window = TimeWindow::ending_at(
+            start + chrono::Duration::minutes(1),
+            chrono::Duration::minutes(1),
+        );
+
+        assert_eq!(window.start, start);
+        assert!(window.contains(start));
+        assert!(!window.contains(window.end));
+        assert!(window.is_contiguous_with(&window.next()));
+        assert_eq!(window.next().duration(), chrono::Duration::minutes(1));
+
+        let cron = Schedule::from(CronSchedule::parse("0 * * * *").unwrap());
+        let end = Utc.with_ymd_and_hms(2024, 1, 1, 1, 0, 0).unwrap();
+        assert_eq!(cron.window_ending(end), TimeWindow::new(start, end));
+        assert_eq!(
+            Schedule::Aligned(TaskInterval::Minutes(1))
+                .window_ending(end)
+                .duration(),
+            chrono::Duration::minutes(1)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_contiguous_windows() {
+        let (ctx, mut receiver, _) = ContextBuilder::<TestData, TestResult>::new()
+            .with_task(TestTask { multiplier: 2 })
+            .with_interval(TaskInterval::Millis(50))
+            .build();
+
+        let runner = crate::Runner::new(ctx);
+        let handle = runner.handle();
+        let runner_handle = tokio::spawn(async move { runner.run().await });
+
+        let mut windows = Vec::new();
+        for _ in 0..4 {
+            let dataset = tokio::time::timeout(Duration::from_millis(200), receiver.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            windows.push(dataset.window);
+        }
+        for pair in windows.windows(2) {
+            assert!(pair[0].is_contiguous_with(&pair[1]));
+            assert_eq!(pair[1].duration(), chrono::Duration::milliseconds(50));
+        }
+
+        handle.shutdown().unwrap();
+        runner_handle.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_manual_clock() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 30).unwrap();
+        let clock = ManualClock::new(start);
+
+        let (ctx, mut receiver, _) = ContextBuilder::<TestData, TestResult>::new()
+            .with_task(TestTask { multiplier: 2 })
+            .with_schedule(Schedule::Aligned(TaskInterval::Minutes(1)))
+            .with_clock(clock.clone())
+            .build();
+
+        let runner = crate::Runner::new(ctx);
+        let handle = runner.handle();
+        let runner_handle = tokio::spawn(async move { runner.run().await });
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(receiver.try_recv().is_err());
+
+        for minute in 1..=3 {
+            clock.advance(chrono::Duration::seconds(60));
+            let dataset = receiver.recv().await.unwrap();
+            assert_eq!(
+                dataset.window,
+                TimeWindow::new(
+                    Utc.with_ymd_and_hms(2024, 1, 1, 0, minute - 1, 0).unwrap(),
+                    Utc.with_ymd_and_hms(2024, 1, 1, 0, minute, 0).unwrap(),
+                )
+            );
+        }
+
+        handle.shutdown().unwrap();
+        runner_handle.await.unwrap().unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_tokio_clock() {
+        let origin = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+
+        let (ctx, mut receiver, _) = ContextBuilder::<TestData, TestResult>::new()
+            .with_task(TestTask { multiplier: 2 })
+            .with_interval(TaskInterval::Seconds(5))
+            .with_clock(TokioClock::new(origin))
+            .build();
+
+        let runner = crate::Runner::new(ctx);
+        let handle = runner.handle();
+        let runner_handle = tokio::spawn(async move { runner.run().await });
+
+        for tick in 0..4 {
+            let dataset = receiver.recv().await.unwrap();
+            let end = origin + chrono::Duration::seconds(5 * tick);
+            assert_eq!(
+                dataset.window,
+                TimeWindow::ending_at(end, chrono::Duration::seconds(5))
+            );
+        }
+
+        handle.shutdown().unwrap();
+        runner_handle.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_backfill() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let half_minute = Schedule::Aligned(TaskInterval::Seconds(30));
+
+        let (ctx, mut receiver, _) = ContextBuilder::<TestData, TestResult>::new()
+            .with_task(TestTask { multiplier: 2 })
+            .with_task_config(
+                TestTask { multiplier: 3 },
+                TaskConfig::default().with_schedule(half_minute.clone()),
+            )
+            .with_interval(TaskInterval::Seconds(20))
+            .build();
+
+        let runner = crate::Runner::new(ctx);
+        let backfill = tokio::spawn(async move {
+            runner
+                .backfill(start, start + chrono::Duration::seconds(70))
+                .await
+        });
+
+        let mut received = Vec::new();
+        while let Some(dataset) = receiver.recv().await {
+            let name = dataset.iter().next().unwrap().0.clone();
+            received.push((name, dataset.window));
+        }
+        backfill.await.unwrap().unwrap();
+
+        let window = |from: i64, to: i64| {
+            TimeWindow::new(
+                start + chrono::Duration::seconds(from),
+                start + chrono::Duration::seconds(to),
+            )
+        };
+        assert_eq!(
+            received,
+            vec![
+                ("TestTask_2".to_string(), window(0, 20)),
+                ("TestTask_3".to_string(), window(0, 30)),
+                ("TestTask_2".to_string(), window(20, 40)),
+                ("TestTask_2".to_string(), window(40, 60)),
+                ("TestTask_3".to_string(), window(30, 60)),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_missed_tick_policy() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let at = |secs: i64| start + chrono::Duration::seconds(secs);
+
+        for policy in [
+            MissedTickPolicy::Burst,
+            MissedTickPolicy::Delay,
+            MissedTickPolicy::Skip,
+        ] {
+            let clock = ManualClock::new(start);
+            let (ctx, mut receiver, _) = ContextBuilder::<TestData, TestResult>::new()
+                .with_task(TestTask { multiplier: 2 })
+                .with_interval(TaskInterval::Seconds(10))
+                .with_config(RunnerConfig::default().with_missed_ticks(policy))
+                .with_clock(clock.clone())
+                .build();
+
+            let runner = crate::Runner::new(ctx);
+            let handle = runner.handle();
+            let runner_handle = tokio::spawn(async move { runner.run().await });
+
+            let first = receiver.recv().await.unwrap();
+            assert_eq!(first.window, TimeWindow::new(at(-10), at(0)));
+
+            clock.advance(chrono::Duration::seconds(35));
+            let mut received = Vec::new();
+            while let Ok(Some(dataset)) =
+                tokio::time::timeout(Duration::from_millis(50), receiver.recv()).await
+            {
+                received.push((dataset.window, dataset.skipped));
+            }
+
+            let expected = match policy {
+                MissedTickPolicy::Burst => vec![
+                    (TimeWindow::new(at(0), at(10)), None),
+                    (TimeWindow::new(at(10), at(20)), None),
+                    (TimeWindow::new(at(20), at(30)), None),
+                ],
+                MissedTickPolicy::Delay => vec![(TimeWindow::new(at(0), at(35)), None)],
+                MissedTickPolicy::Skip => vec![(
+                    TimeWindow::new(at(20), at(30)),
+                    Some(TimeWindow::new(at(0), at(20))),
+                )],
+            };
+            assert_eq!(received, expected, "{:?}", policy);
+
+            clock.advance(chrono::Duration::seconds(10));
+            let next = receiver.recv().await.unwrap();
+            let expected = match policy {
+                MissedTickPolicy::Delay => TimeWindow::new(at(35), at(45)),
+                _ => TimeWindow::new(at(30), at(40)),
+            };
+            assert_eq!(next.window, expected, "{:?}", policy);
+            assert!(next.skipped.is_none());
+
+            handle.shutdown().unwrap();
+            runner_handle.await.unwrap().unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_start_delay_phase_and_jitter() {
+        let at = |min: u32, sec: u32| Utc.with_ymd_and_hms(2024, 1, 1, 0, min, sec).unwrap();
+        let clock = ManualClock::new(at(0, 30));
+
+        let (ctx, mut receiver, _) = ContextBuilder::<TestData, TestResult>::new()
+            .with_task(TestTask { multiplier: 2 })
+            .with_schedule(Schedule::Aligned(TaskInterval::Minutes(1)))
+            .with_clock(clock.clone())
+            .with_start_delay(Duration::from_secs(120))
+            .with_phase_offset(Duration::from_secs(10))
+            .with_jitter(Duration::from_secs(5))
+            .build();
+
+        let runner = crate::Runner::new(ctx);
+        let handle = runner.handle();
+        let runner_handle = tokio::spawn(async move { runner.run().await });
+
+        for now in [at(0, 30), at(1, 15), at(2, 15), at(3, 9)] {
+            clock.set(now);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            assert!(receiver.try_recv().is_err(), "fired early at {}", now);
+        }
+
+        for minute in 3..=5 {
+            clock.set(at(minute, 15));
+            let dataset = tokio::time::timeout(Duration::from_secs(1), receiver.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(
+                dataset.window,
+                TimeWindow::new(at(minute - 1, 0), at(minute, 0))
+            );
+            assert!(dataset.skipped.is_none());
+        }
+
+        handle.shutdown().unwrap();
+        runner_handle.await.unwrap().unwrap();
+    }
+
+    #[test]
+    fn test_task_interval_conversions() {
+        assert_eq!("250ms".parse(), Ok(TaskInterval::Millis(250)));
+        assert_eq!("5s".parse(), Ok(TaskInterval::Seconds(5)));
+        assert_eq!("1h30m".parse(), Ok(TaskInterval::Minutes(90)));
+        assert_eq!("2d".parse(), Ok(TaskInterval::Days(2)));
+        assert_eq!("1s500ms".parse(), Ok(TaskInterval::Millis(1500)));
+        assert_eq!(" 60m ".parse(), Ok(TaskInterval::Hours(1)));
+
+        for invalid in [
+            "",
+            "5",
+            "ms",
+            "5x",
+            "0s",
+            "1h-5m",
+            "99999999999999999999d",
+            "200000000d",
+            "9223372036854775808us",
+        ] {
+            assert!(
+                matches!(
+                    invalid.parse::<TaskInterval>(),
+                    Err(ParseError::InvalidInterval(_))
+                ),
+                "{:?} should not parse",
+                invalid
+            );
+        }
+
+        for interval in [
+            TaskInterval::Micros(7),
+            TaskInterval::Millis(250),
+            TaskInterval::Minutes(90),
+            TaskInterval::Days(1),
+        ] {
+            assert_eq!(interval.to_string().parse(), Ok(interval));
+        }
+
+        assert_eq!(
+            TaskInterval::from(Duration::from_millis(1500)),
+            TaskInterval::Millis(1500)
+        );
+        assert_eq!(
+            Duration::from(TaskInterval::Hours(2)),
+            Duration::from_secs(7200)
+        );
+        assert_eq!(TaskInterval::Millis(1999).as_secs(), 1);
+        assert_eq!(TaskInterval::Days(1).as_millis(), 86_400_000);
+        assert_eq!(
+            "9223372036854775807us".parse(),
+            Ok(TaskInterval::Micros(i64::MAX as u64))
+        );
+
+        // Longer than chrono can add, so it never fires again.
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        for schedule in [
+            Schedule::Interval(TaskInterval::Days(200_000_000)),
+            Schedule::Aligned(TaskInterval::Days(200_000_000)),
+            Schedule::Interval(TaskInterval::Micros(u64::MAX)),
+            Schedule::Aligned(TaskInterval::Micros(u64::MAX)),
+        ] {
+            assert_eq!(schedule.next_after(start), None, "{}", schedule);
+            assert_eq!(schedule.window_at(start).end, start, "{}", schedule);
+        }
+
+        assert_eq!(TaskInterval::Seconds(60), TaskInterval::Minutes(1));
+        assert_ne!(TaskInterval::Seconds(60), TaskInterval::Minutes(2));
+        let intervals: std::collections::HashSet<_> =
+            [TaskInterval::Seconds(60), TaskInterval::Minutes(1)].into();
+        assert_eq!(intervals.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_zero_interval() {
+        let runner_schedule = ContextBuilder::<TestData, TestResult>::new()
+            .with_task(TestTask { multiplier: 1 })
+            .with_interval(TaskInterval::Micros(0))
+            .try_build();
+        assert_eq!(
+            runner_schedule.err(),
+            Some(BuildError::ZeroInterval("the runner".to_string()))
+        );
+
+        let task_schedule = ContextBuilder::<TestData, TestResult>::new()
+            .with_task_config(
+                TestTask { multiplier: 1 },
+                TaskConfig::default().with_schedule(Schedule::Aligned(Duration::ZERO.into())),
+            )
+            .try_build();
+        assert_eq!(
+            task_schedule.err(),
+            Some(BuildError::ZeroInterval("TestTask_1".to_string()))
+        );
+
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        assert_eq!(
+            Schedule::Interval(TaskInterval::Millis(0)).next_after(start),
+            None
+        );
+
+        // A zero interval added at runtime never fires instead of spinning.
+        let (ctx, mut receiver, _) = ContextBuilder::new()
+            .with_task(TestTask { multiplier: 2 })
+            .with_data(Arc::new(RwLock::new(TestData { value: 10 })))
+            .with_interval(TaskInterval::Millis(50))
+            .build();
+        let runner = crate::Runner::new(ctx);
+        let handle = runner.handle();
+        handle
+            .add_task_spec(TaskSpec::new(
+                Task::Sync(Arc::new(TestTask { multiplier: 3 })),
+                TaskConfig::default().with_schedule(Schedule::Interval(TaskInterval::Micros(0))),
+            ))
+            .unwrap();
+        let runner_handle = tokio::spawn(async move { runner.run().await });
+
+        for _ in 0..3 {
+            let dataset = tokio::time::timeout(Duration::from_millis(200), receiver.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(dataset.get("TestTask_2").unwrap().value, 20);
+            assert!(dataset.get("TestTask_3").is_none());
+        }
+
+        handle.shutdown().unwrap();
+        runner_handle.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_input_snapshot() {
+        let data = Arc::new(RwLock::new(TestData { value: 10 }));
+
+        let (ctx, mut receiver, _) = ContextBuilder::new()
+            .with_task_config(
+                SlowTask {
+                    delay: Duration::from_millis(200),
+                },
+                TaskConfig::new(ExecutionMode::Blocking),
+            )
+            .with_task(TestTask { multiplier: 1 })
+            .with_data(data.clone())
+            .with_snapshot(|data: &TestData| TestData { value: data.value })
+            .with_interval(TaskInterval::Seconds(1))
+            .build();
+
+        let runner = crate::Runner::new(ctx);
+        let handle = runner.handle();
+        let runner_handle = tokio::spawn(async move { runner.run().await });
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let mut writer = tokio::time::timeout(Duration::from_millis(50), data.write())
+            .await
+            .expect("writer blocked by a running task");
+        writer.value = 99;
+        drop(writer);
+
+        let dataset = receiver.recv().await.unwrap();
+        assert_eq!(dataset.get("SlowTask").unwrap().value, 10);
+        assert_eq!(dataset.get("TestTask_1").unwrap().value, 10);
+
+        handle.shutdown().unwrap();
+        runner_handle.await.unwrap().unwrap();
+    }
+
+    struct CounterSource {
+        fail: bool,
+    }
+
+    impl DataSource<TestData> for CounterSource {
+        fn name(&self) -> String {
+            "CounterSource".to_string()
+        }
+
+        fn pull<'a>(
+            &'a self,
+            data: &'a mut TestData,
+            _window: TimeWindow,
+        ) -> BoxFuture<'a, Result<(), SourceError>> {
+            Box::pin(async move {
+                if self.fail {
+                    return Err(SourceError::Other("feed down".to_string()));
+                }
+                data.value += 1;
+                Ok(())
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pull_source() {
+        let (ctx, mut receiver, _) = ContextBuilder::new()
+            .with_task(TestTask { multiplier: 1 })
+            .with_data(Arc::new(RwLock::new(TestData { value: 0 })))
+            .with_source(CounterSource { fail: false })
+            .with_interval(TaskInterval::Millis(50))
+            .build();
+
+        let runner = crate::Runner::new(ctx);
+        let handle = runner.handle();
+        let runner_handle = tokio::spawn(async move { runner.run().await });
+
+        let first = receiver.recv().await.unwrap();
+        let second = receiver.recv().await.unwrap();
+        assert_eq!(first.get("TestTask_1").unwrap().value, 1);
+        assert_eq!(second.get("TestTask_1").unwrap().value, 2);
+
+        handle.shutdown().unwrap();
+        runner_handle.await.unwrap().unwrap();
+
+        let (ctx, mut receiver, _) = ContextBuilder::new()
+            .with_task(TestTask { multiplier: 1 })
+            .with_data(Arc::new(RwLock::new(TestData { value: 5 })))
+            .with_source(CounterSource { fail: true })
+            .with_interval(TaskInterval::Millis(50))
+            .build();
+
+        let runner = crate::Runner::new(ctx);
+        let handle = runner.handle();
+        let runner_handle = tokio::spawn(async move { runner.run().await });
+
+        let dataset = receiver.recv().await.unwrap();
+        assert_eq!(dataset.get("TestTask_1").unwrap().value, 5);
+        assert!(matches!(
+            dataset.status("CounterSource"),
+            Some(TaskStatus::Failed(_))
+        ));
+
+        handle.shutdown().unwrap();
+        runner_handle.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_channel_source() {
+        let data = Arc::new(RwLock::new(TestData { value: 0 }));
+        let (feed, updates) = tokio::sync::mpsc::channel(8);
+
+        let (ctx, mut receiver, _) = ContextBuilder::new()
+            .with_task(TestTask { multiplier: 1 })
+            .with_data(data.clone())
+            .with_source(ChannelSource::new(
+                "Feed",
+                updates,
+                |data: &mut TestData, value: i32| data.value += value,
+            ))
+            .with_interval(TaskInterval::Millis(100))
+            .build();
+
+        let runner = crate::Runner::new(ctx);
+        let handle = runner.handle();
+        let runner_handle = tokio::spawn(async move { runner.run().await });
+
+        let first = receiver.recv().await.unwrap();
+        assert_eq!(first.get("TestTask_1").unwrap().value, 0);
+
+        feed.send(3).await.unwrap();
+        feed.send(4).await.unwrap();
+
+        let second = receiver.recv().await.unwrap();
+        assert_eq!(second.get("TestTask_1").unwrap().value, 7);
+        assert_eq!(data.read().await.value, 7);
+
+        handle.shutdown().unwrap();
+        runner_handle.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_sources_stop_when_runner_fails() {
+        let data = Arc::new(RwLock::new(TestData { value: 10 }));
+        let (feed, updates) = tokio::sync::mpsc::channel(8);
+
+        let (ctx, mut receiver, _) = ContextBuilder::new()
+            .with_task_config(
+                FlakyTask {
+                    failures: 1,
+                    calls: Arc::new(AtomicUsize::new(0)),
+                },
+                TaskConfig::default().with_failure_policy(FailurePolicy::Abort),
+            )
+            .with_data(data)
+            .with_source(ChannelSource::new(
+                "Feed",
+                updates,
+                |data: &mut TestData, value: i32| data.value += value,
+            ))
+            .with_interval(TaskInterval::Millis(100))
+            .build();
+
+        let runner = crate::Runner::new(ctx);
+        let runner_handle = tokio::spawn(async move { runner.run().await });
+
+        assert!(runner_handle.await.unwrap().is_err());
+        assert!(receiver.recv().await.is_some());
+        // The feed's receiver is only released once its push task is gone.
+        tokio::time::timeout(Duration::from_millis(100), feed.closed())
+            .await
+            .unwrap();
+    }
+
+    struct SumTask {
+        name: &'static str,
+        upstream: Vec<&'static str>,
+    }
+
+    impl Runnable<TestData, TestResult> for SumTask {
+        fn name(&self) -> String {
+            self.name.to_string()
+        }
+
+        fn run(
+            &self,
+            _data: &TestData,
+            _window: TimeWindow,
+        ) -> Result<TestResult, TaskError<TestResult>> {
+            Err(TaskError::TaskError("needs upstream outputs".to_string()))
+        }
+
+        fn run_with_upstream(
+            &self,
+            _data: &TestData,
+            upstream: &DataSet<TestResult>,
+            _window: TimeWindow,
+        ) -> Result<TestResult, TaskError<TestResult>> {
+            let value = self
+                .upstream
+                .iter()
+                .map(|name| upstream.get(name).map(|result| result.value))
+                .sum::<Option<i32>>()
+                .ok_or_else(|| TaskError::TaskError("missing upstream".to_string()))?;
+            Ok(TestResult { value })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_task_dependencies() {
+        let (ctx, mut receiver, _) = ContextBuilder::new()
+            .with_task(SumTask {
+                name: "Total",
+                upstream: vec!["Sum", "TestTask_3"],
+            })
+            .with_task(SumTask {
+                name: "Sum",
+                upstream: vec!["TestTask_1", "TestTask_2"],
+            })
+            .with_tasks(vec![
+                TestTask { multiplier: 1 },
+                TestTask { multiplier: 2 },
+                TestTask { multiplier: 3 },
+            ])
+            .with_task(FlakyTask {
+                failures: usize::MAX,
+                calls: Arc::new(AtomicUsize::new(0)),
+            })
+            .with_task(SumTask {
+                name: "AfterFlaky",
+                upstream: vec!["FlakyTask"],
+            })
+            .with_task(SumTask {
+                name: "AfterAfterFlaky",
+                upstream: vec!["AfterFlaky"],
+            })
+            .with_dependency("Sum", "TestTask_1")
+            .with_dependency("Sum", "TestTask_2")
+            .with_dependency("Total", "Sum")
+            .with_dependency("Total", "TestTask_3")
+            .with_dependency("AfterFlaky", "FlakyTask")
+            .with_dependency("AfterAfterFlaky", "AfterFlaky")
+            .with_cloned_outputs()
+            .with_data(Arc::new(RwLock::new(TestData { value: 10 })))
+            .with_interval(TaskInterval::Millis(100))
+            .build();
+
+        let runner = crate::Runner::new(ctx);
+        let handle = runner.handle();
+        let runner_handle = tokio::spawn(async move { runner.run().await });
+
+        let dataset = receiver.recv().await.unwrap();
+        assert_eq!(dataset.get("Sum").unwrap().value, 30);
+        assert_eq!(dataset.get("Total").unwrap().value, 60);
+        assert!(matches!(
+            dataset.status("FlakyTask"),
+            Some(TaskStatus::Failed(_))
+        ));
+        assert!(matches!(
+            dataset.status("AfterFlaky"),
+            Some(TaskStatus::Skipped(_))
+        ));
+        assert!(matches!(
+            dataset.status("AfterAfterFlaky"),
+            Some(TaskStatus::Skipped(_))
+        ));
+
+        handle.shutdown().unwrap();
+        runner_handle.await.unwrap().unwrap();
+    }
+
+    #[test]
+    fn test_invalid_dependencies() {
+        let cycle = ContextBuilder::<TestData, TestResult>::new()
+            .with_tasks(vec![
+                TestTask { multiplier: 1 },
+                TestTask { multiplier: 2 },
+                TestTask { multiplier: 3 },
+            ])
+            .with_dependency("TestTask_1", "TestTask_2")
+            .with_dependency("TestTask_2", "TestTask_1")
+            .with_dependency("TestTask_3", "TestTask_1")
+            .with_cloned_outputs()
+            .try_build();
+        assert_eq!(
+            cycle.err(),
+            Some(BuildError::Cycle(vec![
+                "TestTask_1".to_string(),
+                "TestTask_2".to_string(),
+                "TestTask_3".to_string(),
+            ]))
+        );
+
+        let unknown = ContextBuilder::<TestData, TestResult>::new()
+            .with_task(TestTask { multiplier: 1 })
+            .with_dependency("TestTask_1", "Missing")
+            .with_cloned_outputs()
+            .try_build();
+        assert_eq!(
+            unknown.err(),
+            Some(BuildError::UnknownTask("Missing".to_string()))
+        );
+
+        let duplicate = ContextBuilder::<TestData, TestResult>::new()
+            .with_tasks(vec![TestTask { multiplier: 1 }, TestTask { multiplier: 1 }])
+            .try_build();
+        assert_eq!(
+            duplicate.err(),
+            Some(BuildError::DuplicateTask("TestTask_1".to_string()))
+        );
+
+        let no_clone = ContextBuilder::<TestData, TestResult>::new()
+            .with_tasks(vec![TestTask { multiplier: 1 }, TestTask { multiplier: 2 }])
+            .with_dependency("TestTask_2", "TestTask_1")
+            .try_build();
+        assert_eq!(no_clone.err(), Some(BuildError::MissingOutputClone));
+
+        let mismatch = ContextBuilder::<TestData, TestResult>::new()
+            .with_task(TestTask { multiplier: 1 })
+            .with_task_config(
+                TestTask { multiplier: 2 },
+                TaskConfig::default().with_schedule(TaskInterval::Seconds(1)),
+            )
+            .with_dependency("TestTask_2", "TestTask_1")
+            .with_cloned_outputs()
+            .try_build();
+        assert!(matches!(
+            mismatch.err(),
+            Some(BuildError::ScheduleMismatch { .. })
+        ));
+
+        let same_length = ContextBuilder::<TestData, TestResult>::new()
+            .with_task_config(
+                TestTask { multiplier: 1 },
+                TaskConfig::default().with_schedule(TaskInterval::Seconds(60)),
+            )
+            .with_task_config(
+                TestTask { multiplier: 2 },
+                TaskConfig::default().with_schedule(TaskInterval::Minutes(1)),
+            )
+            .with_dependency("TestTask_2", "TestTask_1")
+            .with_cloned_outputs()
+            .try_build();
+        assert!(same_length.is_ok());
+    }
+
+    struct RunningTotal {
+        total: i32,
+        seen: Arc<std::sync::Mutex<Vec<Vec<i32>>>>,
+    }
+
+    impl StatefulRunnable<TestData, TestResult> for RunningTotal {
+        fn name(&self) -> String {
+            "RunningTotal".to_string()
+        }
+
+        fn run(
+            &mut self,
+            data: &TestData,
+            history: &[TestResult],
+            _window: TimeWindow,
+        ) -> Result<TestResult, TaskError<TestResult>> {
+            self.seen
+                .lock()
+                .unwrap()
+                .push(history.iter().map(|result| result.value).collect());
+            self.total += data.value;
+            Ok(TestResult { value: self.total })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stateful_task() {
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let task = RunningTotal {
+            total: 0,
+            seen: seen.clone(),
+        };
+
+        let (ctx, mut receiver, _) = ContextBuilder::new()
+            .with_task(Stateful::new(task).with_cloned_history(2))
+            .with_data(Arc::new(RwLock::new(TestData { value: 10 })))
+            .with_interval(TaskInterval::Millis(50))
+            .build();
+
+        let runner = crate::Runner::new(ctx);
+        let handle = runner.handle();
+        let runner_handle = tokio::spawn(async move { runner.run().await });
+
+        for expected in [10, 20, 30, 40] {
+            let dataset = receiver.recv().await.unwrap();
+            assert_eq!(dataset.get("RunningTotal").unwrap().value, expected);
+        }
+        assert_eq!(
+            seen.lock().unwrap()[..4],
+            [vec![], vec![10], vec![10, 20], vec![20, 30]]
+        );
+
+        handle.shutdown().unwrap();
+        runner_handle.await.unwrap().unwrap();
+    }
+
+    struct OverrunningTotal {
+        total: i32,
+        calls: usize,
+    }
+
+    impl StatefulRunnable<TestData, TestResult> for OverrunningTotal {
+        fn name(&self) -> String {
+            "OverrunningTotal".to_string()
+        }
+
+        fn run(
+            &mut self,
+            data: &TestData,
+            history: &[TestResult],
+            _window: TimeWindow,
+        ) -> Result<TestResult, TaskError<TestResult>> {
+            self.calls += 1;
+            if self.calls == 1 {
+                std::thread::sleep(Duration::from_millis(150));
+            }
+            self.total += data.value;
+            Ok(TestResult {
+                value: self.total * 100 + history.len() as i32,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stateful_task_overrun() {
+        let task = OverrunningTotal { total: 0, calls: 0 };
+
+        let (ctx, mut receiver, _) = ContextBuilder::new()
+            .with_task_config(
+                Stateful::new(task).with_cloned_history(2),
+                TaskConfig::new(ExecutionMode::Blocking).with_timeout(Duration::from_millis(50)),
+            )
+            .with_data(Arc::new(RwLock::new(TestData { value: 10 })))
+            .with_interval(TaskInterval::Millis(100))
+            .build();
+
+        let runner = crate::Runner::new(ctx);
+        let handle = runner.handle();
+        let runner_handle = tokio::spawn(async move { runner.run().await });
+
+        let first = receiver.recv().await.unwrap();
+        assert_eq!(
+            first.status("OverrunningTotal"),
+            Some(&TaskStatus::TimedOut)
+        );
+        let second = receiver.recv().await.unwrap();
+        assert_eq!(
+            second.status("OverrunningTotal"),
+            Some(&TaskStatus::Failed(
+                "Task Error: Previous run of task OverrunningTotal is still in progress"
+                    .to_string()
+            ))
+        );
+        // The overrunning run updated the total but its discarded output is
+        // left out of the history.
+        let third = receiver.recv().await.unwrap();
+        assert_eq!(third.get("OverrunningTotal").unwrap().value, 2000);
+        let fourth = receiver.recv().await.unwrap();
+        assert_eq!(fourth.get("OverrunningTotal").unwrap().value, 3001);
+
+        handle.shutdown().unwrap();
+        runner_handle.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_rollup() {
+        let (sender, receiver) = data_channel(64, BackpressurePolicy::Block);
+        let mut rollup = Rollup::new(receiver, TaskInterval::Minutes(1)).with_merge(
+            "Volume",
+            |a: TestResult, b: TestResult| TestResult {
+                value: a.value + b.value,
+            },
+        );
+
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        let step = chrono::Duration::seconds(5);
+        for i in 0..15 {
+            let mut dataset =
+                DataSet::new(TimeWindow::new(start + step * i, start + step * (i + 1)));
+            dataset.insert("Volume", TestResult { value: 1 });
+            dataset.insert("Close", TestResult { value: i });
+            if i == 3 {
+                dataset.set_status("Feed", TaskStatus::Failed("down".to_string()));
+            }
+            sender.send(dataset).await.unwrap();
+        }
+        drop(sender);
+
+        let minute = rollup.recv().await.unwrap();
+        assert_eq!(
+            minute.window,
+            TimeWindow::new(start, start + chrono::Duration::minutes(1))
+        );
+        assert_eq!(minute.get("Volume").unwrap().value, 12);
+        assert_eq!(minute.get("Close").unwrap().value, 11);
+        assert!(matches!(minute.status("Feed"), Some(TaskStatus::Failed(_))));
+        assert_eq!(
+            minute.schedule,
+            Some(Schedule::Aligned(TaskInterval::Minutes(1)))
+        );
+
+        let partial = rollup.recv().await.unwrap();
+        assert_eq!(
+            partial.window,
+            TimeWindow::new(
+                start + chrono::Duration::minutes(1),
+                start + chrono::Duration::minutes(2)
+            )
+        );
+        assert_eq!(partial.get("Volume").unwrap().value, 3);
+        assert_eq!(partial.get("Close").unwrap().value, 14);
+        assert!(partial.is_ok());
+
+        assert!(rollup.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_rollup_unaligned() {
+        let (sender, receiver) = data_channel(64, BackpressurePolicy::Block);
+        let mut rollup = Rollup::new(receiver, TaskInterval::Minutes(1)).with_merge(
+            "Volume",
+            |a: TestResult, b: TestResult| TestResult {
+                value: a.value + b.value,
+            },
+        );
+
+        let minute = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        let start = minute + chrono::Duration::seconds(2);
+        let step = chrono::Duration::seconds(5);
+        for i in 0..15 {
+            let mut dataset =
+                DataSet::new(TimeWindow::new(start + step * i, start + step * (i + 1)));
+            dataset.insert("Volume", TestResult { value: 1 });
+            sender.send(dataset).await.unwrap();
+        }
+        drop(sender);
+
+        // 12:00:57 - 12:01:02 straddles the boundary, so it is rejected and
+        // reported on both sides instead of widening either window.
+        let first = rollup.recv().await.unwrap();
+        let boundary = minute + chrono::Duration::minutes(1);
+        assert_eq!(first.window, TimeWindow::new(minute, boundary));
+        assert_eq!(first.get("Volume").unwrap().value, 11);
+        assert_eq!(
+            first.skipped,
+            Some(TimeWindow::new(start + step * 11, boundary))
+        );
+
+        let second = rollup.recv().await.unwrap();
+        assert_eq!(
+            second.window,
+            TimeWindow::new(boundary, boundary + chrono::Duration::minutes(1))
+        );
+        assert_eq!(second.get("Volume").unwrap().value, 3);
+        assert_eq!(
+            second.skipped,
+            Some(TimeWindow::new(boundary, start + step * 12))
+        );
+
+        assert!(rollup.recv().await.is_none());
+        assert_eq!(rollup.rejected_datasets(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_rollup_single_cadence() {
+        let (sender, receiver) = data_channel(64, BackpressurePolicy::Block);
+        let mut rollup = Rollup::new(receiver, TaskInterval::Minutes(1)).with_merge(
+            "Volume",
+            |a: TestResult, b: TestResult| TestResult {
+                value: a.value + b.value,
+            },
+        );
+
+        // One receiver carries both the 5s and the hourly cadence.
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        let step = chrono::Duration::seconds(5);
+        for i in 0..12 {
+            let mut dataset =
+                DataSet::new(TimeWindow::new(start + step * i, start + step * (i + 1)))
+                    .with_schedule(Schedule::Interval(TaskInterval::Seconds(5)));
+            dataset.insert("Volume", TestResult { value: 1 });
+            sender.send(dataset).await.unwrap();
+            if i == 5 {
+                let mut hourly =
+                    DataSet::new(TimeWindow::new(start - chrono::Duration::hours(1), start))
+                        .with_schedule(Schedule::Interval(TaskInterval::Hours(1)));
+                hourly.insert("Volume", TestResult { value: 100 });
+                sender.send(hourly).await.unwrap();
+            }
+        }
+        drop(sender);
+
+        let minute = rollup.recv().await.unwrap();
+        assert_eq!(
+            minute.window,
+            TimeWindow::new(start, start + chrono::Duration::minutes(1))
+        );
+        assert_eq!(minute.get("Volume").unwrap().value, 12);
+        assert_eq!(minute.skipped, None);
+
+        assert!(rollup.recv().await.is_none());
+        assert_eq!(rollup.rejected_datasets(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_subscribers() {
+        let (ctx, _, _) = ContextBuilder::new()
+            .with_task(TestTask { multiplier: 2 })
+            .with_data(Arc::new(RwLock::new(TestData { value: 21 })))
+            .with_interval(TaskInterval::Millis(50))
+            .build();
+        let runner = crate::Runner::new(ctx);
+        assert!(runner.subscribe(4, BackpressurePolicy::DropOldest).is_err());
+        assert!(runner.watch().is_err());
+
+        let (ctx, receiver_to_drop, _) = ContextBuilder::new()
+            .with_task(TestTask { multiplier: 2 })
+            .with_data(Arc::new(RwLock::new(TestData { value: 21 })))
+            .with_cloned_outputs()
+            .with_interval(TaskInterval::Millis(50))
+            .build();
+        let runner = crate::Runner::new(ctx);
+        let handle = runner.handle();
+        let mut fast = runner.subscribe(16, BackpressurePolicy::Block).unwrap();
+        let mut slow = handle.subscribe(1, BackpressurePolicy::DropOldest).unwrap();
+        let mut merged = handle.subscribe(1, BackpressurePolicy::Coalesce).unwrap();
+        let mut latest = handle.watch().unwrap();
+        let runner_handle = tokio::spawn(async move { runner.run().await });
+
+        let mut windows = Vec::new();
+        for _ in 0..3 {
+            let dataset = fast.recv().await.unwrap();
+            assert_eq!(dataset.get("TestTask_2").unwrap().value, 42);
+            windows.push(dataset.window);
+        }
+        latest.changed().await.unwrap();
+        assert_eq!(
+            latest
+                .borrow()
+                .as_ref()
+                .and_then(|dataset| dataset.get("TestTask_2"))
+                .map(|result| result.value),
+            Some(42)
+        );
+
+        // Each subscriber made room its own way.
+        assert_eq!(slow.len(), 1);
+        assert!(slow.recv().await.unwrap().window.start >= windows[2].start);
+        let merged = merged.recv().await.unwrap();
+        assert_eq!(merged.window.start, windows[0].start);
+        assert!(merged.window.end >= windows[2].end);
+
+        // Without the original receiver, subscribers keep the runner going.
+        drop(receiver_to_drop);
+        fast.recv().await.unwrap();
+        fast.recv().await.unwrap();
+
+        handle.shutdown().unwrap();
+        runner_handle.await.unwrap().unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_backpressure_policy() {
+        let origin = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let at = |secs| origin + chrono::Duration::seconds(secs);
+
+        // The receiver holds one DataSet; (dropped, coalesced, first, second).
+        for (policy, dropped, coalesced, first, second) in [
+            (
+                BackpressurePolicy::Block,
+                0,
+                0,
+                TimeWindow::new(at(-5), at(0)),
+                TimeWindow::new(at(0), at(5)),
+            ),
+            (
+                BackpressurePolicy::DropNewest,
+                4,
+                0,
+                TimeWindow::new(at(-5), at(0)),
+                TimeWindow::new(at(20), at(25)),
+            ),
+            (
+                BackpressurePolicy::DropOldest,
+                4,
+                0,
+                TimeWindow::new(at(15), at(20)),
+                TimeWindow::new(at(20), at(25)),
+            ),
+            (
+                BackpressurePolicy::Coalesce,
+                0,
+                4,
+                TimeWindow::new(at(-5), at(20)),
+                TimeWindow::new(at(20), at(25)),
+            ),
+        ] {
+            let (ctx, mut receiver, _) = ContextBuilder::<TestData, TestResult>::new()
+                .with_task(TestTask { multiplier: 2 })
+                .with_interval(TaskInterval::Seconds(5))
+                .with_clock(TokioClock::new(origin))
+                .with_config(
+                    RunnerConfig::new(1, 16, Duration::from_secs(5)).with_backpressure(policy),
+                )
+                .build();
+
+            let runner = crate::Runner::new(ctx);
+            let handle = runner.handle();
+            let runner_handle = tokio::spawn(async move { runner.run().await });
+
+            // Ticks at 0s, 5s, 10s, 15s and 20s while nobody reads.
+            tokio::time::sleep(Duration::from_secs(22)).await;
+            assert_eq!(handle.dropped_datasets(), dropped, "{:?}", policy);
+            assert_eq!(handle.coalesced_datasets(), coalesced, "{:?}", policy);
+
+            assert_eq!(receiver.recv().await.unwrap().window, first, "{:?}", policy);
+            assert_eq!(
+                receiver.recv().await.unwrap().window,
+                second,
+                "{:?}",
+                policy
+            );
+
+            handle.shutdown().unwrap();
+            runner_handle.await.unwrap().unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_coalesce_takes_newer_status() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        let step = chrono::Duration::seconds(5);
+        let window = |i| TimeWindow::new(start + step * i, start + step * (i + 1));
+
+        let (sender, mut receiver) = data_channel(1, BackpressurePolicy::Coalesce);
+        let mut first = DataSet::new(window(0));
+        first.set_status("Feed", TaskStatus::Failed("down".to_string()));
+        first.insert("Close", TestResult { value: 1 });
+        sender.send(first).await.unwrap();
+
+        let mut second = DataSet::new(window(1));
+        second.insert("Feed", TestResult { value: 2 });
+        second.set_status("Close", TaskStatus::TimedOut);
+        assert!(matches!(
+            sender.send(second).await.unwrap(),
+            Delivery::Coalesced(_)
+        ));
+
+        let coalesced = receiver.recv().await.unwrap();
+        assert_eq!(coalesced.window, TimeWindow::new(start, start + step * 2));
+        assert_eq!(coalesced.status("Feed"), Some(&TaskStatus::Ok));
+        assert_eq!(coalesced.get("Feed").unwrap().value, 2);
+        assert_eq!(coalesced.status("Close"), Some(&TaskStatus::TimedOut));
+        assert!(coalesced.get("Close").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_abort_while_delivery_blocked() {
+        let (ctx, receiver, _) = ContextBuilder::<TestData, TestResult>::new()
+            .with_task(TestTask { multiplier: 2 })
+            .with_interval(TaskInterval::Millis(10))
+            .with_config(
+                RunnerConfig::new(1, 16, Duration::from_secs(60))
+                    .with_backpressure(BackpressurePolicy::Block),
+            )
+            .build();
+
+        let runner = crate::Runner::new(ctx);
+        let handle = runner.handle();
+        let runner_handle = tokio::spawn(async move { runner.run().await });
+
+        // The receiver is never read, so delivery blocks from the second tick.
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(receiver.len(), 1);
+
+        handle.abort().unwrap();
+        let result = tokio::time::timeout(Duration::from_secs(1), runner_handle)
+            .await
+            .expect("abort did not stop a runner blocked on delivery");
+        result.unwrap().unwrap();
+        assert_eq!(handle.dropped_datasets(), 1);
+    }
+
     #[tokio::test]
     async fn test_data_update() {
         let data = Arc::new(RwLock::new(TestData { value: 10 }));
@@ -0,0 +1,55 @@
+use std::fmt;
+
+use chrono::{DateTime, Duration, Utc};
+
+// Windows are half-open, [start, end), so consecutive windows share a
+// boundary without overlapping.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TimeWindow {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl TimeWindow {
+    pub fn new(start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        TimeWindow { start, end }
+    }
+
+    // A window reaching back past the earliest representable time starts
+    // there instead.
+    pub fn ending_at(end: DateTime<Utc>, length: Duration) -> Self {
+        let start = end
+            .checked_sub_signed(length)
+            .unwrap_or(DateTime::<Utc>::MIN_UTC);
+        TimeWindow::new(start, end)
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.end - self.start
+    }
+
+    pub fn contains(&self, time: DateTime<Utc>) -> bool {
+        self.start <= time && time < self.end
+    }
+
+    pub fn next(&self) -> Self {
+        TimeWindow::new(self.end, self.end + self.duration())
+    }
+
+    pub fn is_contiguous_with(&self, next: &TimeWindow) -> bool {
+        self.end == next.start
+    }
+}
+
+impl fmt::Display for TimeWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{}, {})",
+            self.start
+                .to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true),
+            self.end
+                .to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true)
+        )
+    }
+}